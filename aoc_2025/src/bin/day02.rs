@@ -2,7 +2,7 @@ use aoc_utils::{input, parser::*};
 
 fn is_invalid(n: usize) -> bool {
     let s = n.to_string();
-    s.len() % 2 == 0 && s[..s.len() / 2] == s[s.len() / 2..]
+    s.len().is_multiple_of(2) && s[..s.len() / 2] == s[s.len() / 2..]
 }
 
 fn is_invalid2(n: usize) -> bool {
@@ -6,7 +6,7 @@ use std::collections::HashMap;
 fn joltage<'a>(
     digits: &'a [usize],
     n: usize,
-    mut cache: &mut HashMap<(&'a [usize], usize), usize>,
+    cache: &mut HashMap<(&'a [usize], usize), usize>,
 ) -> usize {
     if n == 0 {
         return 0;
@@ -17,9 +17,7 @@ fn joltage<'a>(
     }
 
     let max = (0..=digits.len() - n)
-        .map(|i| {
-            digits[i] * 10_usize.pow(n as u32 - 1) + joltage(&digits[i + 1..], n - 1, &mut cache)
-        })
+        .map(|i| digits[i] * 10_usize.pow(n as u32 - 1) + joltage(&digits[i + 1..], n - 1, cache))
         .max()
         .unwrap();
 
@@ -30,8 +28,10 @@ fn joltage<'a>(
 fn main() {
     let banks = input::parse_lines(
         any_char()
-            .map(|c| c.to_digit(10).unwrap() as usize)
-            .repeat(..),
+            .try_map(|c| c.to_digit(10))
+            .map(|d| d as usize)
+            .repeat(..)
+            << eof(),
     );
 
     for i in [2, 12] {
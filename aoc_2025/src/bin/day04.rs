@@ -23,8 +23,8 @@ fn valid_rolls(rolls: &[Vec<bool>]) -> Vec<(usize, usize)> {
         .collect()
 }
 
-fn remove_valids(rolls: &mut Vec<Vec<bool>>) -> usize {
-    let mut v = valid_rolls(&rolls);
+fn remove_valids(rolls: &mut [Vec<bool>]) -> usize {
+    let mut v = valid_rolls(rolls);
     let mut count = 0;
 
     while !v.is_empty() {
@@ -34,7 +34,7 @@ fn remove_valids(rolls: &mut Vec<Vec<bool>>) -> usize {
             rolls[r][c] = false;
         }
 
-        v = valid_rolls(&rolls);
+        v = valid_rolls(rolls);
     }
 
     count
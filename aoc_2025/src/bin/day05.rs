@@ -17,8 +17,10 @@ fn get_fresh(ranges: &mut [(usize, usize)]) -> usize {
 }
 
 fn main() {
-    let mut ranges = input::parse_ok_lines(number() + (prefix("-") >> number()));
-    let nums = input::parse_ok_lines(number() << eof());
+    let (mut ranges, nums) = input::parse_sections((
+        (number() + (prefix("-") >> number())).sep_by(newline(), 1..),
+        number().sep_by(newline(), 1..),
+    ));
 
     let part1 = nums
         .iter()
@@ -36,8 +36,8 @@ fn column_numbes(digits_grid: &[Vec<char>], indexes: &[usize]) -> Vec<Vec<usize>
 }
 
 fn main() {
-    let numbers = input::parse_ok_lines(number().anywhere().repeat(1..));
-    let ops = input::parse_ok_lines((prefix("*") | prefix("+")).anywhere().repeat(1..));
+    let numbers = input::parse_ok_lines(spaces() >> number().lexeme().repeat(1..));
+    let ops = input::parse_ok_lines(spaces() >> (symbol("*") | symbol("+")).repeat(1..));
     let ops = &ops[0];
 
     let numbers = (0..numbers[0].len())
@@ -51,9 +51,8 @@ fn main() {
     let part1 = total(&numbers, ops);
     println!("{part1}");
 
-    let ops_chars = input::parse_ok_lines(
-        prefix("+").anywhere().look_ahead() >> satisfies(|c| " +*".contains(c)).repeat(1..),
-    );
+    let ops_chars =
+        input::parse_ok_lines(prefix("+").anywhere().look_ahead() >> one_of(" +*").repeat(1..));
 
     let ops_indexes = ops_chars[0]
         .iter()
@@ -64,9 +63,7 @@ fn main() {
         .chain([ops_chars[0].len() - 1])
         .collect::<Vec<_>>();
 
-    let digits_grid = input::parse_ok_lines(
-        satisfies(|c| c.is_ascii_digit() || c == ' ').repeat(ops_indexes[1]..),
-    );
+    let digits_grid = input::parse_ok_lines(one_of(" 0123456789").repeat(ops_indexes[1]..));
 
     let part2 = total(&column_numbes(&digits_grid, &ops_indexes), ops);
     println!("{part2}");
@@ -1,18 +1,16 @@
 use std::collections::{BTreeSet, HashSet, VecDeque};
 
-use aoc_utils::{input, parser::*};
+use aoc_utils::input;
 use itertools::Itertools;
 
 fn square_dst(p1: &[usize], p2: &[usize]) -> usize {
     p1.iter().zip(p2).map(|(a, b)| (b - a).pow(2)).sum()
 }
 
-fn groups_and_edges(
-    positions: &[Vec<usize>],
-) -> (
-    VecDeque<HashSet<&Vec<usize>>>,
-    BTreeSet<(usize, &Vec<usize>, &Vec<usize>)>,
-) {
+type Groups<'a> = VecDeque<HashSet<&'a [usize; 3]>>;
+type Edges<'a> = BTreeSet<(usize, &'a [usize; 3], &'a [usize; 3])>;
+
+fn groups_and_edges(positions: &[[usize; 3]]) -> (Groups<'_>, Edges<'_>) {
     let mut groups = VecDeque::new();
     let mut edges = BTreeSet::new();
     for i in 0..positions.len() {
@@ -33,9 +31,9 @@ fn groups_and_edges(
 }
 
 fn merge_closest<'a>(
-    groups: &mut VecDeque<HashSet<&'a Vec<usize>>>,
-    edges: &mut BTreeSet<(usize, &'a Vec<usize>, &'a Vec<usize>)>,
-) -> (&'a Vec<usize>, &'a Vec<usize>) {
+    groups: &mut VecDeque<HashSet<&'a [usize; 3]>>,
+    edges: &mut BTreeSet<(usize, &'a [usize; 3], &'a [usize; 3])>,
+) -> (&'a [usize; 3], &'a [usize; 3]) {
     let (_, a, b) = edges.pop_first().unwrap();
     let mut acc = HashSet::new();
     for _ in 0..groups.len() {
@@ -50,11 +48,11 @@ fn merge_closest<'a>(
     }
     groups.push_back(acc);
 
-    (&a, &b)
+    (a, b)
 }
 
 fn main() {
-    let positions = input::parse_lines(number().sep_by(prefix(","), 3..=3));
+    let positions = input::lines::<[usize; 3]>();
 
     let (mut groups, mut edges) = groups_and_edges(&positions);
 
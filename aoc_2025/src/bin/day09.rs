@@ -1,4 +1,4 @@
-use aoc_utils::{input, parser::*};
+use aoc_utils::input;
 
 use std::ops::RangeInclusive;
 
@@ -54,7 +54,7 @@ fn valid(a: (usize, usize), b: (usize, usize), edges: &[Edge]) -> bool {
 }
 
 fn main() {
-    let coords = &input::parse_lines(number() + (prefix(",") >> number()));
+    let coords = &input::lines::<(usize, usize)>();
     let part1 = (0..coords.len())
         .flat_map(|i| (i + 1..coords.len()).map(move |j| area(coords[i], coords[j])))
         .max()
@@ -65,7 +65,7 @@ fn reachable_with_2(
         }
     }
 
-    return false;
+    false
 }
 
 fn fewest_presses_2(counters: &[usize], buttons: &[Vec<usize>]) -> usize {
@@ -75,16 +75,20 @@ fn fewest_presses_2(counters: &[usize], buttons: &[Vec<usize>]) -> usize {
 }
 
 fn main() {
-    let lights = prefix("[")
-        >> (prefix("#").map(|_| true) | prefix(".").map(|_| false)).repeat(1..)
-        << prefix("]");
-    let buttons = prefix(" (") >> number().sep_by(prefix(","), 1..) << prefix(")");
-    let counters = prefix(" {") >> number().sep_by(prefix(","), 1..) << prefix("}");
-    let machines = input::parse_lines(lights + buttons.repeat(1..) + counters);
+    let lights = (prefix("#").value(true) | prefix(".").value(false))
+        .repeat(1..)
+        .between(prefix("["), symbol("]"));
+    let buttons = number()
+        .sep_by(prefix(","), 1..)
+        .between(prefix("("), symbol(")"));
+    let counters = number()
+        .sep_by(prefix(","), 1..)
+        .between(prefix("{"), symbol("}"));
+    let machines = input::parse_lines((lights, buttons.repeat(1..), counters));
 
     let part1 = machines
         .iter()
-        .map(|((l, b), _)| fewest_presses(l, b))
+        .map(|(l, b, _)| fewest_presses(l, b))
         .sum::<usize>();
     println!("{part1}");
 
@@ -92,7 +96,7 @@ fn main() {
     // Maybe later i try optimizing it or trying a different approach.
     let part2 = machines
         .iter()
-        .map(|((_, b), c)| fewest_presses_2(c, b))
+        .map(|(_, b, c)| fewest_presses_2(c, b))
         .sum::<usize>();
     println!("{part2}");
 }
@@ -16,22 +16,17 @@ fn different_paths<'a>(node: &'a str, graph: &HashMap<&'a str, Vec<&'a str>>) ->
 }
 
 fn main() {
-    let ids = input::parse_lines(
-        satisfies(char::is_lowercase)
-            .repeat(1..)
-            .collect_string()
-            .anywhere()
-            .repeat(2..),
-    );
+    let content = input::get_content();
+    let device = take_while1(char::is_lowercase);
 
-    let mut map: HashMap<&str, _> = HashMap::new();
-    for i in &ids {
-        let mut v: Vec<&str> = vec![];
-        for j in &i[1..] {
-            v.push(j);
-        }
-        map.insert(&i[0], v);
-    }
+    let map = content
+        .lines()
+        .map(|line| {
+            ((device << prefix(":")) + (prefix(" ") >> device).repeat(1..))
+                .run_parser(line)
+                .expect("Failed parsing input file")
+        })
+        .collect::<HashMap<_, _>>();
 
     let part1 = different_paths("you", &map);
     println!("{part1}");
@@ -0,0 +1,64 @@
+//! Tokenizer of the format strings used by `#[derive(FromAocStr)]` and `scan!`.
+
+use syn::{Error, LitStr, Result};
+
+/// A piece of a format string.
+pub enum Segment {
+    Literal(String),
+    /// A placeholder with the name between its braces, empty for `{}`.
+    Placeholder(String),
+}
+
+/// Splits a format string into literals and placeholders, turning `{{` and `}}` into literal braces.
+pub fn segments(format: &LitStr) -> Result<Vec<Segment>> {
+    let text = format.value();
+    let error = |message: &str| Error::new(format.span(), message);
+
+    let mut segments = vec![];
+    let mut literal = String::new();
+    let mut chars = text.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        match c {
+            '{' if chars.peek() == Some(&'{') => {
+                chars.next();
+                literal.push('{');
+            }
+            '}' if chars.peek() == Some(&'}') => {
+                chars.next();
+                literal.push('}');
+            }
+            '{' => {
+                let mut name = String::new();
+                loop {
+                    match chars.next() {
+                        Some('}') => break,
+                        Some(c) => name.push(c),
+                        None => {
+                            return Err(error(
+                                "unclosed `{` in format, use `{{` for a literal brace",
+                            ));
+                        }
+                    }
+                }
+
+                if !literal.is_empty() {
+                    segments.push(Segment::Literal(std::mem::take(&mut literal)));
+                }
+                segments.push(Segment::Placeholder(name));
+            }
+            '}' => {
+                return Err(error(
+                    "unmatched `}` in format, use `}}` for a literal brace",
+                ));
+            }
+            c => literal.push(c),
+        }
+    }
+
+    if !literal.is_empty() {
+        segments.push(Segment::Literal(literal));
+    }
+
+    Ok(segments)
+}
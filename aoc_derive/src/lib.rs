@@ -0,0 +1,508 @@
+//! Procedural macros that build parsers from type definitions and format strings.
+//!
+//! The macros are re-exported by `aoc_utils::parser`, which is where they should be used from.
+
+mod format;
+mod scan;
+
+use format::Segment;
+use proc_macro2::TokenStream;
+use quote::{format_ident, quote};
+use syn::{
+    Attribute, Data, DeriveInput, Error, Expr, Fields, GenericArgument, LitInt, LitStr,
+    PathArguments, Result, Type, parse_macro_input, parse_quote, spanned::Spanned,
+};
+
+/// Implements `FromAocStr` for a struct or an enum, so it can be parsed with `aoc::<T>()`,
+/// `input::parse::<T>()` or `input::lines::<T>()`.
+///
+/// # Attributes
+/// On the type, or on each variant of an enum:
+/// * `#[aoc(format = "...")]`: The text around the fields, with `{field}` for each field
+///   (`{0}`, `{1}`... in tuple structs) and `{{`, `}}` for literal braces. A field followed by text
+///   is parsed from the input up to the first occurrence of that text, and must consume all of it.
+///   Without a format, the fields are separated by the default separators of `FromAocStr`.
+/// * `#[aoc(level = n)]`: The `LEVEL` of the type. By default, it is 1 for formats in a single line,
+///   2 for formats with line breaks, and one more than the deepest field without a format.
+///   The `LEVEL` of an enum is the deepest of its variants.
+///
+/// On each field:
+/// * `#[aoc(sep = "...")]`: The field is a `Vec` whose elements are separated by the given text.
+/// * `#[aoc(with = expr)]`: The field is parsed with the parser `expr` instead of the one inferred
+///   from its type.
+///
+/// Fields are parsed with `aoc::<T>()` by default, so they can be of any type implementing
+/// `FromAocStr`, including other derived types. The variants of an enum are tried in order.
+/// Every type parameter of a generic type is required to implement `FromAocStr`.
+///
+/// # Examples
+/// ```
+/// use aoc_utils::parser::*;
+///
+/// #[derive(FromAocStr, Debug, PartialEq)]
+/// #[aoc(format = "({wires})")]
+/// struct Button {
+///     #[aoc(sep = ",")]
+///     wires: Vec<usize>,
+/// }
+///
+/// #[derive(FromAocStr, Debug, PartialEq)]
+/// #[aoc(format = "[{lights}] {buttons} {{{counters}}}")]
+/// struct Machine {
+///     #[aoc(with = (prefix("#").value(true) | prefix(".").value(false)).repeat(..))]
+///     lights: Vec<bool>,
+///     #[aoc(sep = " ")]
+///     buttons: Vec<Button>,
+///     counters: Vec<usize>,
+/// }
+///
+/// assert_eq!(
+///     aoc::<Machine>().run_parser("[.#] (1) (0,1) {3,5}"),
+///     Some(Machine {
+///         lights: vec![false, true],
+///         buttons: vec![Button { wires: vec![1] }, Button { wires: vec![0, 1] }],
+///         counters: vec![3, 5],
+///     })
+/// );
+///
+/// #[derive(FromAocStr, Debug, PartialEq)]
+/// enum Instruction {
+///     #[aoc(format = "noop")]
+///     Noop,
+///     #[aoc(format = "addx {0}")]
+///     Add(i32),
+///     #[aoc(format = "move {n} from {from} to {to}")]
+///     Move { n: usize, from: usize, to: usize },
+/// }
+///
+/// assert_eq!(
+///     aoc::<Vec<Instruction>>().run_parser("addx -3\nnoop\nmove 1 from 2 to 3"),
+///     Some(vec![
+///         Instruction::Add(-3),
+///         Instruction::Noop,
+///         Instruction::Move { n: 1, from: 2, to: 3 },
+///     ])
+/// );
+///
+/// #[derive(FromAocStr, Debug, PartialEq)]
+/// struct Pair<T> {
+///     left: T,
+///     right: T,
+/// }
+///
+/// assert_eq!(aoc::<Pair<i32>>().run_parser("3 -4"), Some(Pair { left: 3, right: -4 }));
+/// assert_eq!(aoc::<Vec<Pair<u8>>>().run_parser("1 2\n3 4").map(|v| v.len()), Some(2));
+///
+/// #[derive(FromAocStr, Debug, PartialEq)]
+/// enum Shape {
+///     #[aoc(format = "circle {0}")]
+///     Circle(u32),
+///     Polygon(Vec<u32>),
+/// }
+///
+/// assert_eq!(<Shape as FromAocStr>::LEVEL, 2);
+/// assert_eq!(aoc::<Shape>().run_parser("1,2,3"), Some(Shape::Polygon(vec![1, 2, 3])));
+/// ```
+#[proc_macro_derive(FromAocStr, attributes(aoc))]
+pub fn derive_from_aoc_str(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    expand(&input)
+        .unwrap_or_else(Error::into_compile_error)
+        .into()
+}
+
+/// Options of `#[aoc(...)]` on a type or a variant.
+#[derive(Default)]
+struct ShapeOptions {
+    format: Option<LitStr>,
+    level: Option<LitInt>,
+}
+
+/// Options of `#[aoc(...)]` on a field.
+#[derive(Default)]
+struct FieldOptions {
+    sep: Option<LitStr>,
+    with: Option<Expr>,
+}
+
+/// A field of a struct or a variant.
+struct Field {
+    /// The name used in the format: the identifier, or the index in tuple structs.
+    name: String,
+    /// The token used to construct the value: the identifier or the index.
+    member: syn::Member,
+    ty: Type,
+    options: FieldOptions,
+}
+
+fn shape_options(attrs: &[Attribute]) -> Result<ShapeOptions> {
+    let mut options = ShapeOptions::default();
+
+    for attr in attrs.iter().filter(|attr| attr.path().is_ident("aoc")) {
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("format") {
+                options.format = Some(meta.value()?.parse()?);
+            } else if meta.path.is_ident("level") {
+                options.level = Some(meta.value()?.parse()?);
+            } else {
+                return Err(meta.error("expected `format` or `level`"));
+            }
+            Ok(())
+        })?;
+    }
+
+    Ok(options)
+}
+
+fn field_options(attrs: &[Attribute]) -> Result<FieldOptions> {
+    let mut options = FieldOptions::default();
+
+    for attr in attrs.iter().filter(|attr| attr.path().is_ident("aoc")) {
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("sep") {
+                options.sep = Some(meta.value()?.parse()?);
+            } else if meta.path.is_ident("with") {
+                options.with = Some(meta.value()?.parse()?);
+            } else {
+                return Err(meta.error("expected `sep` or `with`"));
+            }
+            Ok(())
+        })?;
+    }
+
+    if let (Some(_), Some(with)) = (&options.sep, &options.with) {
+        return Err(Error::new(
+            with.span(),
+            "`sep` and `with` can't be used together",
+        ));
+    }
+
+    Ok(options)
+}
+
+fn fields(fields: &Fields) -> Result<Vec<Field>> {
+    fields
+        .iter()
+        .enumerate()
+        .map(|(i, field)| {
+            let (name, member) = match &field.ident {
+                Some(ident) => (ident.to_string(), syn::Member::Named(ident.clone())),
+                None => (i.to_string(), syn::Member::Unnamed(i.into())),
+            };
+
+            Ok(Field {
+                name,
+                member,
+                ty: field.ty.clone(),
+                options: field_options(&field.attrs)?,
+            })
+        })
+        .collect()
+}
+
+/// Splits a format string into literals and fields, checking that every field appears exactly once.
+fn segments(format: &LitStr, fields: &[Field]) -> Result<Vec<Segment>> {
+    let segments = format::segments(format)?;
+    let error = |message: String| Error::new(format.span(), message);
+    let appears = |name: &str| {
+        segments
+            .iter()
+            .filter(|segment| matches!(segment, Segment::Placeholder(f) if f == name))
+            .count()
+    };
+
+    for segment in &segments {
+        if let Segment::Placeholder(name) = segment {
+            if !fields.iter().any(|field| field.name == *name) {
+                return Err(error(format!("unknown field `{name}` in format")));
+            }
+            if appears(name) > 1 {
+                return Err(error(format!(
+                    "field `{name}` appears more than once in format"
+                )));
+            }
+        }
+    }
+
+    if let Some(field) = fields.iter().find(|field| appears(&field.name) == 0) {
+        return Err(error(format!(
+            "field `{}` is missing from format",
+            field.name
+        )));
+    }
+
+    Ok(segments)
+}
+
+/// Returns `T` if `ty` is `Vec<T>`.
+fn vec_element(ty: &Type) -> Option<&Type> {
+    let Type::Path(path) = ty else {
+        return None;
+    };
+
+    let last = path.path.segments.last()?;
+    let PathArguments::AngleBracketed(args) = &last.arguments else {
+        return None;
+    };
+
+    match args.args.first() {
+        Some(GenericArgument::Type(element)) if last.ident == "Vec" => Some(element),
+        _ => None,
+    }
+}
+
+/// Builds the expression of the parser of a field.
+fn field_parser(field: &Field) -> Result<TokenStream> {
+    let ty = &field.ty;
+
+    if let Some(with) = &field.options.with {
+        return Ok(quote!((#with)));
+    }
+
+    if let Some(sep) = &field.options.sep {
+        let element = vec_element(ty)
+            .ok_or_else(|| Error::new(ty.span(), "fields with `sep` must be of type `Vec<T>`"))?;
+        return Ok(quote! {
+            ::aoc_utils::parser::aoc::<#element>().sep_by(::aoc_utils::parser::prefix(#sep), 1..)
+        });
+    }
+
+    Ok(quote!(::aoc_utils::parser::aoc::<#ty>()))
+}
+
+/// Returns the `LEVEL` of a field in a type without a format.
+fn field_level(field: &Field) -> TokenStream {
+    let ty = &field.ty;
+
+    match (&field.options.with, &field.options.sep, vec_element(ty)) {
+        (Some(_), _, _) => quote!(0),
+        (_, Some(_), Some(element)) => {
+            quote!(<#element as ::aoc_utils::parser::FromAocStr>::LEVEL + 1)
+        }
+        _ => quote!(<#ty as ::aoc_utils::parser::FromAocStr>::LEVEL),
+    }
+}
+
+/// Returns the `LEVEL` of a struct or a variant.
+fn level(options: &ShapeOptions, fields: &[Field]) -> TokenStream {
+    if let Some(level) = &options.level {
+        return quote!(#level);
+    }
+
+    match &options.format {
+        Some(format) if format.value().contains('\n') => quote!(2),
+        Some(_) => quote!(1),
+        None => {
+            let levels = fields.iter().map(field_level).collect::<Vec<_>>();
+            quote! {{
+                let mut level = 0;
+                #(if #levels > level {
+                    level = #levels;
+                })*
+                level + 1
+            }}
+        }
+    }
+}
+
+/// Builds the body of a closure that parses a struct or a variant built by `constructor`.
+fn parse_body(
+    constructor: TokenStream,
+    options: &ShapeOptions,
+    fields: &[Field],
+) -> Result<TokenStream> {
+    let var = |field: &Field| format_ident!("__{}", field.name.trim_start_matches("r#"));
+    let mut steps = vec![];
+
+    match &options.format {
+        Some(format) => {
+            let segments = segments(format, fields)?;
+
+            for (i, segment) in segments.iter().enumerate() {
+                match segment {
+                    Segment::Literal(text) => steps.push(quote! {
+                        let (_, input) = ::aoc_utils::parser::prefix(#text).parse(input)?;
+                    }),
+                    Segment::Placeholder(name) => {
+                        let field = fields.iter().find(|field| field.name == *name).unwrap();
+                        let var = var(field);
+                        let parser = field_parser(field)?;
+
+                        steps.push(match segments.get(i + 1) {
+                            Some(Segment::Literal(next)) => quote! {
+                                let (#var, input) = ::aoc_utils::parser::take_until(#next)
+                                    .try_map(|s| match #parser.parse(s) {
+                                        Some((value, "")) => Some(value),
+                                        _ => None,
+                                    })
+                                    .parse(input)?;
+                            },
+                            _ => quote! {
+                                let (#var, input) = #parser.parse(input)?;
+                            },
+                        });
+                    }
+                }
+            }
+        }
+        None => {
+            for (i, field) in fields.iter().enumerate() {
+                if i > 0 {
+                    steps.push(quote! {
+                        let (_, input) = ::aoc_utils::parser::aoc_separator(Self::LEVEL, input)?;
+                    });
+                }
+
+                let var = var(field);
+                let parser = field_parser(field)?;
+                steps.push(quote! {
+                    let (#var, input) = #parser.parse(input)?;
+                });
+            }
+        }
+    }
+
+    let members = fields.iter().map(|field| &field.member);
+    let vars = fields.iter().map(var);
+
+    Ok(quote! {
+        #(#steps)*
+        Some((#constructor { #(#members: #vars),* }, input))
+    })
+}
+
+fn expand(input: &DeriveInput) -> Result<TokenStream> {
+    let name = &input.ident;
+    let mut generics = input.generics.clone();
+    let params = generics
+        .type_params()
+        .map(|param| param.ident.clone())
+        .collect::<Vec<_>>();
+    let where_clause = generics.make_where_clause();
+    for param in params {
+        where_clause
+            .predicates
+            .push(parse_quote!(#param: ::aoc_utils::parser::FromAocStr));
+    }
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+    let options = shape_options(&input.attrs)?;
+
+    let (level, body) = match &input.data {
+        Data::Struct(data) => {
+            let fields = fields(&data.fields)?;
+            let level = level(&options, &fields);
+            let body = parse_body(quote!(Self), &options, &fields)?;
+            (level, body)
+        }
+        Data::Enum(data) => {
+            if let Some(format) = &options.format {
+                return Err(Error::new(
+                    format.span(),
+                    "the format of an enum goes on each variant",
+                ));
+            }
+
+            let mut levels = vec![];
+            let variants = data
+                .variants
+                .iter()
+                .map(|variant| {
+                    let options = shape_options(&variant.attrs)?;
+                    let fields = fields(&variant.fields)?;
+                    levels.push(level(&options, &fields));
+
+                    if fields.is_empty() && options.format.is_none() {
+                        return Err(Error::new(
+                            variant.span(),
+                            "variants without fields need `#[aoc(format = \"...\")]`",
+                        ));
+                    }
+
+                    let variant = &variant.ident;
+                    let body = parse_body(quote!(Self::#variant), &options, &fields)?;
+                    Ok(quote! {
+                        .or_else(|| (|| -> Option<(Self, &'input str)> { #body })())
+                    })
+                })
+                .collect::<Result<Vec<_>>>()?;
+
+            let level = match &options.level {
+                Some(level) => quote!(#level),
+                None => quote! {{
+                    let mut level = 0;
+                    #(if #levels > level {
+                        level = #levels;
+                    })*
+                    level
+                }},
+            };
+
+            (level, quote!(None #(#variants)*))
+        }
+        Data::Union(data) => {
+            return Err(Error::new(
+                data.union_token.span(),
+                "FromAocStr can't be derived for unions",
+            ));
+        }
+    };
+
+    Ok(quote! {
+        impl #impl_generics ::aoc_utils::parser::FromAocStr for #name #ty_generics #where_clause {
+            const LEVEL: usize = #level;
+
+            fn parse_aoc<'input>(input: &'input str) -> Option<(Self, &'input str)> {
+                use ::aoc_utils::parser::Parser as _;
+                #body
+            }
+        }
+    })
+}
+
+/// Creates a parser from a format string with a `{}` placeholder for each value, like the ones of
+/// `format!`, and the types of the values.
+///
+/// The text around the placeholders is parsed with `prefix` and each value with `aoc::<T>()`, so it
+/// can be of any type implementing `FromAocStr`. A value followed by text is parsed from the input
+/// up to the first occurrence of that text, and must consume all of it. A `&str` value takes the
+/// input up to the text that follows it, or up to the end of the line if it is the last thing in
+/// the format. `{{` and `}}` are literal braces.
+///
+/// The format is checked at compile time: placeholders must be empty and there must be as many of
+/// them as types.
+///
+/// # Returns
+/// A new `Parser` that produces a tuple with the values, or the value on its own if there is only
+/// one.
+///
+/// # Examples
+/// ```
+/// use aoc_utils::parser::*;
+///
+/// let policy = scan!("{}-{} {}: {}", usize, usize, char, &str);
+///
+/// assert_eq!(policy.run_parser("1-3 a: abcde"), Some((1, 3, 'a', "abcde")));
+/// assert_eq!(policy.run_parser("1-3 a abcde"), None);
+///
+/// let velocity = scan!("v={},{}", i32, i32);
+/// assert_eq!(velocity.run_parser("v=-3,7"), Some((-3, 7)));
+///
+/// let claim = scan!("#{} @ {},{}: {}x{}", u32, u32, u32, u32, u32);
+/// assert_eq!(claim.run_parser("#1 @ 2,3: 4x5"), Some((1, 2, 3, 4, 5)));
+///
+/// let id = scan!("Game {}", usize);
+/// assert_eq!(id.run_parser("Game 12"), Some(12));
+///
+/// let set = scan!("{{{}}}", Vec<u8>);
+/// assert_eq!(set.run_parser("{1,2}"), Some(vec![1, 2]));
+///
+/// let tail = scan!("{},{}", Vec<u8>, u8);
+/// assert_eq!(tail.run_parser("1 2,3"), Some((vec![1, 2], 3)));
+/// ```
+#[proc_macro]
+pub fn scan(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    let input = parse_macro_input!(input as scan::ScanInput);
+    scan::expand(&input)
+        .unwrap_or_else(Error::into_compile_error)
+        .into()
+}
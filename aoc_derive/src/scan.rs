@@ -0,0 +1,144 @@
+//! Expansion of `scan!`, which builds a parser from a format string and the types of its values.
+
+use crate::format::{Segment, segments};
+
+use proc_macro2::TokenStream;
+use quote::{format_ident, quote};
+use syn::{
+    Error, LitStr, Result, Token, Type,
+    parse::{Parse, ParseStream},
+    punctuated::Punctuated,
+};
+
+/// The arguments of `scan!`: a format string followed by one type per placeholder.
+pub struct ScanInput {
+    format: LitStr,
+    types: Vec<Type>,
+}
+
+impl Parse for ScanInput {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let format = input.parse()?;
+        let types = if input.is_empty() {
+            vec![]
+        } else {
+            input.parse::<Token![,]>()?;
+            Punctuated::<Type, Token![,]>::parse_terminated(input)?
+                .into_iter()
+                .collect()
+        };
+
+        Ok(ScanInput { format, types })
+    }
+}
+
+/// Returns whether `ty` is `&str`, with any lifetime.
+fn is_str(ty: &Type) -> bool {
+    match ty {
+        Type::Reference(reference) => {
+            reference.mutability.is_none()
+                && matches!(&*reference.elem, Type::Path(path) if path.path.is_ident("str"))
+        }
+        _ => false,
+    }
+}
+
+pub fn expand(input: &ScanInput) -> Result<TokenStream> {
+    let segments = segments(&input.format)?;
+
+    if let Some(Segment::Placeholder(name)) = segments
+        .iter()
+        .find(|segment| matches!(segment, Segment::Placeholder(name) if !name.is_empty()))
+    {
+        return Err(Error::new(
+            input.format.span(),
+            format!("placeholders are written `{{}}`, found `{{{name}}}`"),
+        ));
+    }
+
+    if segments.is_empty() {
+        return Err(Error::new(input.format.span(), "the format can't be empty"));
+    }
+
+    let placeholders = segments
+        .iter()
+        .filter(|segment| matches!(segment, Segment::Placeholder(_)))
+        .count();
+    if placeholders != input.types.len() {
+        return Err(Error::new(
+            input.format.span(),
+            format!(
+                "expected {placeholders} types for the placeholders of the format, found {}",
+                input.types.len()
+            ),
+        ));
+    }
+
+    let mut types = input.types.iter();
+    let mut chain: Option<(TokenStream, TokenStream)> = None;
+    let mut vars = vec![];
+
+    for (i, segment) in segments.iter().enumerate() {
+        let next = match segment {
+            Segment::Literal(text) => quote!(::aoc_utils::parser::prefix(#text)),
+            Segment::Placeholder(_) => {
+                let ty = types.next().unwrap();
+                match (segments.get(i + 1), is_str(ty)) {
+                    (Some(Segment::Literal(text)), true) => {
+                        quote!(::aoc_utils::parser::take_until(#text))
+                    }
+                    (Some(Segment::Literal(text)), false) => quote! {
+                        ::aoc_utils::parser::Parser::try_map(
+                            ::aoc_utils::parser::take_until(#text),
+                            |s| match ::aoc_utils::parser::Parser::parse(
+                                &::aoc_utils::parser::aoc::<#ty>(),
+                                s,
+                            ) {
+                                Some((value, "")) => Some(value),
+                                _ => None,
+                            },
+                        )
+                    },
+                    (Some(Segment::Placeholder(_)), true) => {
+                        return Err(Error::new(
+                            input.format.span(),
+                            "a `&str` placeholder must be followed by text or end the format",
+                        ));
+                    }
+                    (None, true) => quote!(::aoc_utils::parser::take_while(|c| c != '\n')),
+                    (_, false) => quote!(::aoc_utils::parser::aoc::<#ty>()),
+                }
+            }
+        };
+
+        let keep = matches!(segment, Segment::Placeholder(_));
+        let var = format_ident!("__{}", vars.len());
+
+        chain = Some(match (chain, keep) {
+            (None, true) => (next, quote!(#var)),
+            (None, false) => (next, quote!(_)),
+            (Some((parser, pattern)), true) => (
+                quote!(::aoc_utils::parser::Parser::then_zip(#parser, #next)),
+                quote!((#pattern, #var)),
+            ),
+            (Some((parser, pattern)), false) => (
+                quote!(::aoc_utils::parser::Parser::then_ignore(#parser, #next)),
+                pattern,
+            ),
+        });
+
+        if keep {
+            vars.push(var);
+        }
+    }
+
+    let (parser, pattern) = chain.unwrap();
+    let output = match vars.as_slice() {
+        [var] => quote!(#var),
+        vars => quote!((#(#vars),*)),
+    };
+
+    Ok(quote! {
+        ::aoc_utils::parser::Parser::map(#parser, |#pattern| #output)
+    })
+}
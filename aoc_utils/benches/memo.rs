@@ -0,0 +1,44 @@
+//! Compares a backtracking grammar with and without `memo`.
+//!
+//! The grammar `s = "a" s "b" | "a" s "c" | "x"` parses `s` twice at every level when the input
+//! ends in `c`s, so without memoisation the time doubles with each extra `a`.
+//!
+//! Run with `cargo bench -p aoc_utils`.
+
+use aoc_utils::parser::*;
+
+use std::time::{Duration, Instant};
+
+fn plain<'a>() -> BoxedParser<'a, &'static str> {
+    recursive(|s| {
+        (prefix("a") >> s.clone() << prefix("b")) | (prefix("a") >> s << prefix("c")) | prefix("x")
+    })
+    .boxed()
+}
+
+fn memoised<'a>() -> BoxedParser<'a, &'static str> {
+    recursive(|s| {
+        let s = s.memo();
+        (prefix("a") >> s.clone() << prefix("b")) | (prefix("a") >> s << prefix("c")) | prefix("x")
+    })
+    .boxed()
+}
+
+fn time<'a>(parser: &BoxedParser<'a, &'static str>, input: &'a str) -> Duration {
+    let start = Instant::now();
+    assert_eq!(parser.run_parser(input), Some("x"));
+    start.elapsed()
+}
+
+fn main() {
+    println!("{:>6} {:>14} {:>14}", "depth", "plain", "memo");
+
+    for depth in [4, 8, 12, 16, 20] {
+        let input = format!("{}x{}", "a".repeat(depth), "c".repeat(depth));
+
+        let plain = time(&plain(), &input);
+        let memo = time(&memoised(), &input);
+
+        println!("{depth:>6} {plain:>14.2?} {memo:>14.2?}");
+    }
+}
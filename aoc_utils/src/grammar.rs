@@ -0,0 +1,257 @@
+//! Grammars defined at runtime by numbered rules, as found in some puzzle inputs:
+//!
+//! ```text
+//! 0: 4 1 5
+//! 1: 2 3 | 3 2
+//! 4: "a"
+//! ```
+//!
+//! Each rule is either a literal between quotes or a list of alternatives separated by `|`,
+//! each one a sequence of rule ids. Rules can be recursive. Matching keeps track of every
+//! amount of input a rule can consume, so rules like `8: 42 | 42 8` followed by other rules
+//! are handled correctly. Left-recursive rules are not supported.
+
+use crate::parser::{Ebnf, Parser, ParserImpl, Rules, eof, number, p, prefix, take_until};
+
+use std::collections::{BTreeSet, HashMap};
+
+/// A single rule of a `Grammar`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Rule {
+    /// Matches the given text.
+    Literal(String),
+    /// Matches any of the sequences of rules.
+    Alternatives(Vec<Vec<usize>>),
+}
+
+/// A set of numbered rules compiled from text.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Grammar {
+    rules: HashMap<usize, Rule>,
+}
+
+fn rule_line<'a>() -> impl Parser<'a, Output = (usize, Rule)> {
+    let literal = take_until("\"")
+        .between(prefix("\""), prefix("\""))
+        .map(|s: &str| Rule::Literal(s.to_string()));
+
+    let alternatives = number()
+        .sep_by(prefix(" "), 1..)
+        .sep_by(prefix(" | "), 1..)
+        .map(Rule::Alternatives);
+
+    (number() << prefix(": ")) + (literal | alternatives)
+}
+
+impl Grammar {
+    /// Compiles a grammar from its rules, one per line. Empty lines are ignored.
+    ///
+    /// # Arguments
+    /// * `text`: The rules of the grammar.
+    ///
+    /// # Returns
+    /// An `Option<Grammar>` with the compiled grammar, or `None` if any line is not a valid rule
+    /// or a rule refers to an undefined one.
+    ///
+    /// # Examples
+    /// ```
+    /// use aoc_utils::grammar::*;
+    ///
+    /// let grammar = Grammar::compile("0: 1 2\n1: \"a\"\n2: 1 3 | 3 1\n3: \"b\"").unwrap();
+    ///
+    /// assert_eq!(grammar.rule(1), Some(&Rule::Literal("a".to_string())));
+    /// assert_eq!(Grammar::compile("0: 1"), None);
+    /// assert_eq!(Grammar::compile("0 -> 1"), None);
+    /// ```
+    pub fn compile(text: &str) -> Option<Grammar> {
+        let mut grammar = Grammar::default();
+
+        for line in text.lines().filter(|line| !line.trim().is_empty()) {
+            grammar.insert(line)?;
+        }
+
+        grammar.is_complete().then_some(grammar)
+    }
+
+    /// Adds a rule to the grammar, replacing the previous rule with the same id.
+    ///
+    /// References to undefined rules are not checked, so rules can be added in any order.
+    ///
+    /// # Arguments
+    /// * `line`: The rule, like `8: 42 | 42 8`.
+    ///
+    /// # Returns
+    /// An `Option` with the id of the rule, or `None` if `line` is not a valid rule.
+    ///
+    /// # Examples
+    /// ```
+    /// use aoc_utils::grammar::*;
+    ///
+    /// let mut grammar = Grammar::compile("0: 1\n1: 2\n2: \"a\"").unwrap();
+    ///
+    /// assert!(!grammar.matches(0, "aaa"));
+    /// assert_eq!(grammar.insert("1: 2 | 2 1"), Some(1));
+    /// assert!(grammar.matches(0, "aaa"));
+    ///
+    /// // Literals can't be mixed with alternatives.
+    /// assert_eq!(grammar.insert("1: \"a\" | \"a\" 1"), None);
+    /// ```
+    pub fn insert(&mut self, line: &str) -> Option<usize> {
+        let (id, rule) = rule_line().then_ignore(eof()).run_parser(line.trim_end())?;
+        self.rules.insert(id, rule);
+        Some(id)
+    }
+
+    /// Returns the rule with the given id.
+    pub fn rule(&self, id: usize) -> Option<&Rule> {
+        self.rules.get(&id)
+    }
+
+    fn is_complete(&self) -> bool {
+        self.rules.values().all(|rule| match rule {
+            Rule::Literal(_) => true,
+            Rule::Alternatives(alts) => alts.iter().flatten().all(|id| self.rules.contains_key(id)),
+        })
+    }
+
+    /// Computes every amount of bytes that rule `id` can consume from the start of `input`.
+    ///
+    /// # Arguments
+    /// * `id`: The rule to match.
+    /// * `input`: The string slice to match.
+    ///
+    /// # Returns
+    /// A sorted `Vec<usize>` with the lengths of every possible match.
+    ///
+    /// # Examples
+    /// ```
+    /// use aoc_utils::grammar::*;
+    ///
+    /// let grammar = Grammar::compile("0: 1 | 1 0\n1: \"a\"").unwrap();
+    ///
+    /// assert_eq!(grammar.match_lengths(0, "aaab"), vec![1, 2, 3]);
+    /// assert_eq!(grammar.match_lengths(0, "baaa"), vec![]);
+    /// ```
+    pub fn match_lengths(&self, id: usize, input: &str) -> Vec<usize> {
+        self.ends(id, input, 0).into_iter().collect()
+    }
+
+    /// Describes rule `id` as a rule named after its number, along with the rules it uses.
+    fn describe_rule(&self, id: usize, rules: &mut Rules) -> Ebnf {
+        rules.rule(&id.to_string(), |rules| match self.rules.get(&id) {
+            Some(Rule::Literal(text)) => Ebnf::literal(text),
+            Some(Rule::Alternatives(alternatives)) => Ebnf::alternative(
+                alternatives
+                    .iter()
+                    .map(|seq| {
+                        Ebnf::sequence(
+                            seq.iter()
+                                .map(|&id| self.describe_rule(id, rules))
+                                .collect::<Vec<_>>(),
+                        )
+                    })
+                    .collect::<Vec<_>>(),
+            ),
+            None => Ebnf::special("undefined"),
+        })
+    }
+
+    fn ends(&self, id: usize, input: &str, pos: usize) -> BTreeSet<usize> {
+        match self.rules.get(&id) {
+            None => BTreeSet::new(),
+            Some(Rule::Literal(s)) => input[pos..]
+                .starts_with(s.as_str())
+                .then_some(pos + s.len())
+                .into_iter()
+                .collect(),
+            Some(Rule::Alternatives(alts)) => alts
+                .iter()
+                .flat_map(|seq| {
+                    seq.iter().fold(BTreeSet::from([pos]), |positions, &sub| {
+                        positions
+                            .into_iter()
+                            .flat_map(|p| self.ends(sub, input, p))
+                            .collect()
+                    })
+                })
+                .collect(),
+        }
+    }
+
+    /// Checks whether rule `id` matches the whole `message`.
+    ///
+    /// # Arguments
+    /// * `id`: The rule to match.
+    /// * `message`: The string slice to match.
+    ///
+    /// # Returns
+    /// `true` if any way of matching the rule consumes the whole message.
+    ///
+    /// # Examples
+    /// ```
+    /// use aoc_utils::grammar::*;
+    ///
+    /// let rules = "0: 4 1 5\n1: 2 3 | 3 2\n2: 4 4 | 5 5\n3: 4 5 | 5 4\n4: \"a\"\n5: \"b\"";
+    /// let grammar = Grammar::compile(rules).unwrap();
+    ///
+    /// assert!(grammar.matches(0, "ababbb"));
+    /// assert!(grammar.matches(0, "abbbab"));
+    /// assert!(!grammar.matches(0, "bababa"));
+    /// assert!(!grammar.matches(0, "aaaabbb"));
+    ///
+    /// // Rules that can consume different amounts of input
+    /// let mut grammar = Grammar::compile("0: 8 11\n8: 4\n11: 4 5\n4: \"a\"\n5: \"b\"").unwrap();
+    /// grammar.insert("8: 4 | 4 8");
+    /// grammar.insert("11: 4 5 | 4 11 5");
+    ///
+    /// assert!(grammar.matches(0, "aaab"));
+    /// assert!(grammar.matches(0, "aaabb"));
+    /// assert!(!grammar.matches(0, "aabb"));
+    /// ```
+    pub fn matches(&self, id: usize, message: &str) -> bool {
+        self.ends(id, message, 0).contains(&message.len())
+    }
+
+    /// Creates a parser that matches rule `id`, consuming the longest possible amount of input.
+    ///
+    /// # Arguments
+    /// * `id`: The rule to match.
+    ///
+    /// # Returns
+    /// A new `Parser` that produces the matched `&str`.
+    ///
+    /// # Examples
+    /// ```
+    /// use aoc_utils::{grammar::*, parser::*};
+    ///
+    /// let grammar = Grammar::compile("0: 1 | 1 0\n1: \"ab\"").unwrap();
+    /// let words = grammar.parser(0).sep_by(prefix(" "), ..);
+    ///
+    /// assert_eq!(words.run_parser("abab ab"), Some(vec!["abab", "ab"]));
+    /// ```
+    pub fn parser(&self, id: usize) -> ParserImpl<RuleParser<'_>> {
+        p(RuleParser { grammar: self, id })
+    }
+}
+
+/// Parser that matches a rule of a `Grammar`.
+#[derive(Clone, Copy)]
+pub struct RuleParser<'g> {
+    grammar: &'g Grammar,
+    id: usize,
+}
+
+impl<'a> Parser<'a> for RuleParser<'_> {
+    type Output = &'a str;
+
+    fn parse(&self, input: &'a str) -> Option<(Self::Output, &'a str)> {
+        self.grammar
+            .ends(self.id, input, 0)
+            .last()
+            .map(|&len| input.split_at(len))
+    }
+
+    fn describe_into(&self, rules: &mut Rules) -> Ebnf {
+        self.grammar.describe_rule(self.id, rules)
+    }
+}
@@ -2,9 +2,14 @@
 //!
 //! Input files are expected to be located in the `inputs/<year>/<day>` directory.
 
-use crate::parser::Parser;
+use crate::parser::{FromAocStr, Parser, SectionParsers, aoc, eof, split_sections, within};
 
-use std::{env, fs, path::Path};
+use std::{
+    env, fmt,
+    fs::{self, File},
+    io::{self, BufRead, BufReader},
+    path::{Path, PathBuf},
+};
 
 /// Reads the content of the input file for the specified year and day.
 ///
@@ -15,14 +20,33 @@ use std::{env, fs, path::Path};
 /// # Returns
 /// A `String` containing the content of the input file.
 pub fn get_content() -> String {
+    let path = input_path();
+    fs::read_to_string(&path).unwrap_or_else(|e| panic!("Can't open file: {}. {e}", path.display()))
+}
+
+/// Opens the input file for the specified year and day to read it lazily, without loading it whole.
+///
+/// # Panics
+/// - if the command-line arguments for year and day are missing.
+/// - if the input file cannot be opened.
+///
+/// # Returns
+/// A buffered reader over the input file, to be used with [`stream_lines`].
+pub fn get_reader() -> BufReader<File> {
+    let path = input_path();
+    File::open(&path)
+        .map(BufReader::new)
+        .unwrap_or_else(|e| panic!("Can't open file: {}. {e}", path.display()))
+}
+
+/// Returns the path of the input file for the year and day given as command-line arguments.
+fn input_path() -> PathBuf {
     let mut args = env::args();
     args.next();
 
     let year = args.next().expect("Missing command-line argument for year");
     let day = args.next().expect("Missing command-line argument for day");
-    let path = Path::new("inputs").join(year).join(day);
-
-    fs::read_to_string(&path).unwrap_or_else(|e| panic!("Can't open file: {}. {e}", path.display()))
+    Path::new("inputs").join(year).join(day)
 }
 
 /// Reads and parses the content of the input file for the specified year and day.
@@ -34,12 +58,43 @@ pub fn get_content() -> String {
 ///
 /// # Returns
 /// The parsed value of type `T`.
-pub fn parse_input<T>(parser: impl Parser<Output = T>) -> T {
+pub fn parse_input<T>(parser: impl for<'a> Parser<'a, Output = T>) -> T {
     parser
         .run_parser(&get_content())
         .expect("Failed parsing input file")
 }
 
+/// Reads and parses the content of the input file for the specified year and day,
+/// inferring the parser from the type as described in [`FromAocStr`].
+/// Trailing whitespace is ignored.
+///
+/// # Panics
+/// - if the command-line arguments for year and day are missing.
+/// - if the input file cannot be opened.
+/// - if the content isn't a value of type `T`.
+///
+/// # Returns
+/// The parsed value of type `T`.
+pub fn parse<T: FromAocStr>() -> T {
+    (aoc::<T>() << eof())
+        .run_parser(get_content().trim_end())
+        .expect("Failed parsing input file")
+}
+
+/// Reads and parses the lines of the input file for the specified year and day,
+/// inferring the parser from the type as described in [`FromAocStr`].
+///
+/// # Panics
+/// - if the command-line arguments for year and day are missing.
+/// - if the input file cannot be opened.
+/// - if a line isn't a value of type `T`.
+///
+/// # Returns
+/// A `Vec<T>` with the parsed result of each line.
+pub fn lines<T: FromAocStr>() -> Vec<T> {
+    parse_lines(aoc::<T>() << eof())
+}
+
 /// Reads the lines of the input file for the specified year and day.
 ///  
 /// # Panics
@@ -53,6 +108,7 @@ pub fn get_lines() -> Vec<String> {
 }
 
 /// Reads and parses the lines of the input file for the specified year and day.
+/// Positions in the lines, like the ones of [`Parser::with_span`], are relative to the whole file.
 ///
 /// # Panics
 /// - if the command-line arguments for year and day are missing.
@@ -61,15 +117,19 @@ pub fn get_lines() -> Vec<String> {
 ///
 /// # Returns
 /// A `Vec<T>` with the parsed result of each line.
-pub fn parse_lines<T>(parser: impl Parser<Output = T>) -> Vec<T> {
-    get_content()
-        .lines()
-        .map(|line| parser.run_parser(line).expect("Failed parsing input file"))
-        .collect::<Vec<_>>()
+pub fn parse_lines<T>(parser: impl for<'a> Parser<'a, Output = T>) -> Vec<T> {
+    let content = get_content();
+    within(content.as_str(), || {
+        content
+            .lines()
+            .map(|line| parser.run_parser(line).expect("Failed parsing input file"))
+            .collect::<Vec<_>>()
+    })
 }
 
 /// Reads and parses the lines of the input file for the specified year and day.
 /// If the parser fails for a line, that line will be discarded.
+/// Positions in the lines, like the ones of [`Parser::with_span`], are relative to the whole file.
 ///
 /// # Panics
 /// - if the command-line arguments for year and day are missing.
@@ -77,9 +137,172 @@ pub fn parse_lines<T>(parser: impl Parser<Output = T>) -> Vec<T> {
 ///
 /// # Returns
 /// A `Vec<T>` with the parsed result of each line that succeeded.
-pub fn parse_ok_lines<T>(parser: impl Parser<Output = T>) -> Vec<T> {
-    get_content()
-        .lines()
-        .flat_map(|line| parser.run_parser(line))
-        .collect::<Vec<_>>()
+pub fn parse_ok_lines<T>(parser: impl for<'a> Parser<'a, Output = T>) -> Vec<T> {
+    let content = get_content();
+    within(content.as_str(), || {
+        content
+            .lines()
+            .flat_map(|line| parser.run_parser(line))
+            .collect::<Vec<_>>()
+    })
+}
+
+/// Error produced when streaming the lines of an input.
+#[derive(Debug)]
+pub enum LineError {
+    /// The line couldn't be read.
+    Io(io::Error),
+    /// The parser failed on a line, given by its number, starting at 1, and its content.
+    Parse(usize, String),
+}
+
+impl fmt::Display for LineError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            LineError::Io(e) => write!(f, "can't read line: {e}"),
+            LineError::Parse(number, line) => write!(f, "failed parsing line {number}: {line:?}"),
+        }
+    }
+}
+
+impl std::error::Error for LineError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            LineError::Io(e) => Some(e),
+            LineError::Parse(..) => None,
+        }
+    }
+}
+
+/// Iterator that reads and parses the lines of a reader one by one.
+///
+/// Created by [`stream_lines`].
+pub struct StreamLines<R, P> {
+    reader: R,
+    parser: P,
+    buffer: String,
+    number: usize,
+}
+
+/// Lazily reads and parses the lines of a reader, like [`parse_lines`] but keeping only one line
+/// in memory at a time. Line breaks, `\n` or `\r\n`, are not passed to the parser.
+/// As the file isn't read whole, positions like the ones of [`Parser::with_span`] are relative to
+/// each line.
+///
+/// # Arguments
+/// * `reader`: The source of the lines, like [`get_reader`], `io::stdin().lock()` or a byte slice.
+/// * `parser`: The parser for each line.
+///
+/// # Returns
+/// An `Iterator` over the result of parsing each line, which is a [`LineError`] if the line
+/// couldn't be read or parsed.
+///
+/// # Examples
+/// ```
+/// use aoc_utils::{input::*, parser::*};
+///
+/// let ranges = stream_lines("1-3\r\n4-5\nx\n".as_bytes(), number() + (prefix("-") >> number()))
+///     .collect::<Vec<_>>();
+///
+/// assert_eq!(ranges.len(), 3);
+/// assert_eq!(ranges[0].as_ref().ok(), Some(&(1, 3)));
+/// assert_eq!(ranges[1].as_ref().ok(), Some(&(4, 5)));
+/// assert_eq!(ranges[2].as_ref().unwrap_err().to_string(), "failed parsing line 3: \"x\"");
+/// ```
+pub fn stream_lines<R: BufRead, T, P: for<'a> Parser<'a, Output = T>>(
+    reader: R,
+    parser: P,
+) -> StreamLines<R, P> {
+    StreamLines {
+        reader,
+        parser,
+        buffer: String::new(),
+        number: 0,
+    }
+}
+
+impl<R: BufRead, T, P: for<'a> Parser<'a, Output = T>> Iterator for StreamLines<R, P> {
+    type Item = Result<T, LineError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.buffer.clear();
+        match self.reader.read_line(&mut self.buffer) {
+            Ok(0) => return None,
+            Ok(_) => self.number += 1,
+            Err(e) => return Some(Err(LineError::Io(e))),
+        }
+
+        let line = self.buffer.strip_suffix('\n').unwrap_or(&self.buffer);
+        let line = line.strip_suffix('\r').unwrap_or(line);
+
+        Some(
+            self.parser
+                .run_parser(line)
+                .ok_or_else(|| LineError::Parse(self.number, line.to_string())),
+        )
+    }
+}
+
+/// Reads every integer that appears in the input file for the specified year and day.
+/// Any text between the numbers is discarded.
+///
+/// The numbers are parsed as `T` with [`FromAocStr`], so signed types like `i64` take a leading `-`
+/// as part of the number while unsigned types skip it.
+///
+/// # Panics
+/// - if the command-line arguments for year and day are missing.
+/// - if the input file cannot be opened.
+///
+/// # Returns
+/// A `Vec<T>` with the numbers in the order they appear.
+pub fn all_numbers<T: FromAocStr>() -> Vec<T> {
+    aoc::<T>()
+        .iter_matches(&get_content())
+        .map(|(n, _)| n)
+        .collect()
+}
+
+/// Reads the blocks of the input file for the specified year and day. Blocks are separated by blank lines.
+///
+/// # Panics
+/// - if the command-line arguments for year and day are missing.
+/// - if the input file cannot be opened.
+///
+/// # Returns
+/// A `Vec<String>` where each element is a block from the input file, without the trailing line break.
+pub fn get_sections() -> Vec<String> {
+    split_sections(&get_content())
+        .into_iter()
+        .map(str::to_string)
+        .collect()
+}
+
+/// Reads and parses the blocks of the input file for the specified year and day.
+/// Blocks are separated by blank lines and each one is parsed with its own parser.
+/// Positions in the blocks, like the ones of [`Parser::with_span`], are relative to the whole file.
+///
+/// # Panics
+/// - if the command-line arguments for year and day are missing.
+/// - if the input file cannot be opened.
+/// - if the number of blocks differs from the number of parsers.
+/// - if a parser fails or doesn't consume its whole block.
+///
+/// # Returns
+/// A tuple with the parsed value of each block.
+pub fn parse_sections<T>(parsers: impl for<'a> SectionParsers<'a, Output = T>) -> T {
+    fn parse<'a, S: SectionParsers<'a>>(parsers: &S, content: &'a str) -> S::Output {
+        let sections = split_sections(content);
+
+        assert!(
+            sections.len() == S::COUNT,
+            "Expected {} sections in input file but found {}",
+            S::COUNT,
+            sections.len()
+        );
+
+        within(content, || parsers.parse_sections(&sections))
+            .unwrap_or_else(|i| panic!("Failed parsing section {} of input file", i + 1))
+    }
+
+    parse(&parsers, &get_content())
 }
@@ -0,0 +1,223 @@
+//! Primitive parsers over bytes, for inputs parsed as `&[u8]`.
+//!
+//! They mirror the primitives of the same name over `str`, but skip UTF-8 decoding, which makes them
+//! faster on ASCII inputs like grids. Every combinator method works over bytes too, so they can be
+//! combined as usual, while the operators are written with their methods, like `or` for `|`.
+//!
+//! # Examples
+//! ```
+//! use aoc_utils::parser::*;
+//!
+//! let row = bytes::satisfies(|b| b == b'#' || b == b'.').repeat(1..);
+//! let grid = row.sep_by(bytes::prefix(b"\n"), ..);
+//!
+//! assert_eq!(
+//!     grid.run_parser(b"#.\n.#".as_slice()),
+//!     Some(vec![vec![b'#', b'.'], vec![b'.', b'#']])
+//! );
+//!
+//! // Pre-lexed inputs work the same, with the primitives over tokens written as functions.
+//! #[derive(Clone, Copy, Debug, PartialEq)]
+//! enum Token {
+//!     Num(i64),
+//!     Plus,
+//! }
+//!
+//! fn num(input: &[Token]) -> Option<(i64, &[Token])> {
+//!     match input {
+//!         [Token::Num(n), rest @ ..] => Some((*n, rest)),
+//!         _ => None,
+//!     }
+//! }
+//!
+//! fn plus(input: &[Token]) -> Option<((), &[Token])> {
+//!     match input {
+//!         [Token::Plus, rest @ ..] => Some(((), rest)),
+//!         _ => None,
+//!     }
+//! }
+//!
+//! let sum = p(num).sep_by(p(plus), 1..).map(|ns| ns.iter().sum::<i64>());
+//! let tokens = [Token::Num(1), Token::Plus, Token::Num(2)];
+//!
+//! assert_eq!(sum.run_parser(&tokens[..]), Some(3));
+//! ```
+
+use super::{Ebnf, Parser, ParserImpl, Rules};
+
+///////////////////////////////////////////////////
+//                    AnyChar                    //
+///////////////////////////////////////////////////
+
+/// Parser that returns the first byte.
+#[derive(Clone, Copy)]
+pub struct AnyChar {}
+
+/// Creates a parser that returns the first byte.
+///
+/// # Returns
+/// A new `Parser` that produces `u8`.
+///
+/// # Examples
+/// ```
+/// use aoc_utils::parser::*;
+///
+/// assert_eq!(bytes::any_char().parse(b"abc".as_slice()), Some((b'a', b"bc".as_slice())));
+/// assert_eq!(bytes::any_char().run_parser(b"".as_slice()), None);
+/// ```
+pub fn any_char() -> ParserImpl<AnyChar> {
+    ParserImpl(AnyChar {})
+}
+
+impl<'a> Parser<'a, [u8]> for AnyChar {
+    type Output = u8;
+
+    fn parse(&self, input: &'a [u8]) -> Option<(Self::Output, &'a [u8])> {
+        input.split_first().map(|(b, rest)| (*b, rest))
+    }
+
+    fn describe_into(&self, _rules: &mut Rules) -> Ebnf {
+        Ebnf::special("any")
+    }
+}
+
+/////////////////////////////////////////////////////
+//                    Satisfies                    //
+/////////////////////////////////////////////////////
+
+/// Parser that matches a single byte satisfying a given predicate.
+#[derive(Clone, Copy)]
+pub struct Satisfies<F: Fn(u8) -> bool> {
+    f: F,
+}
+
+/// Creates a parser that matches a single byte satisfying a given predicate.
+///
+/// # Arguments
+/// * `f`: A closure that takes a `u8` and returns if the byte satisfies a condition.
+///
+/// # Returns
+/// A new `Parser` that produces the matched `u8`.
+///
+/// # Examples
+/// ```
+/// use aoc_utils::parser::*;
+///
+/// let uppercase = bytes::satisfies(|b| b.is_ascii_uppercase());
+///
+/// assert_eq!(uppercase.run_parser(b"H".as_slice()), Some(b'H'));
+/// assert_eq!(uppercase.run_parser(b"h".as_slice()), None);
+/// ```
+pub fn satisfies<F: Fn(u8) -> bool>(f: F) -> ParserImpl<Satisfies<F>> {
+    ParserImpl(Satisfies { f })
+}
+
+impl<'a, F: Fn(u8) -> bool> Parser<'a, [u8]> for Satisfies<F> {
+    type Output = u8;
+
+    fn parse(&self, input: &'a [u8]) -> Option<(Self::Output, &'a [u8])> {
+        input
+            .split_first()
+            .and_then(|(b, rest)| (self.f)(*b).then_some((*b, rest)))
+    }
+
+    fn describe_into(&self, _rules: &mut Rules) -> Ebnf {
+        Ebnf::class(|c| c.is_ascii() && (self.f)(c as u8))
+    }
+}
+
+//////////////////////////////////////////////////
+//                    Prefix                    //
+//////////////////////////////////////////////////
+
+/// Parser that consumes a prefix of bytes from the input.
+#[derive(Clone, Copy)]
+pub struct Prefix<'b> {
+    p: &'b [u8],
+}
+
+/// Creates a parser that consumes a prefix of bytes from the input.
+///
+/// # Arguments
+/// * `p`: The bytes to match as a prefix.
+///
+/// # Returns
+/// A new `Parser` that produces the prefix.
+///
+/// # Examples
+/// ```
+/// use aoc_utils::parser::*;
+///
+/// let greetings = bytes::prefix(b"hello ");
+///
+/// assert_eq!(
+///     greetings.parse(b"hello Javi".as_slice()),
+///     Some((b"hello ".as_slice(), b"Javi".as_slice()))
+/// );
+/// assert_eq!(greetings.parse(b"Hi Javi".as_slice()), None);
+/// ```
+pub fn prefix<'b>(p: &'b [u8]) -> ParserImpl<Prefix<'b>> {
+    ParserImpl(Prefix { p })
+}
+
+impl<'a, 'b> Parser<'a, [u8]> for Prefix<'b> {
+    type Output = &'b [u8];
+
+    fn parse(&self, input: &'a [u8]) -> Option<(Self::Output, &'a [u8])> {
+        input.strip_prefix(self.p).map(|s| (self.p, s))
+    }
+
+    fn describe_into(&self, _rules: &mut Rules) -> Ebnf {
+        Ebnf::literal(&String::from_utf8_lossy(self.p))
+    }
+}
+
+//////////////////////////////////////////////////
+//                    Number                    //
+//////////////////////////////////////////////////
+
+/// Parser for positive integers written in ASCII digits.
+#[derive(Clone, Copy)]
+pub struct Number {}
+
+/// Creates a parser that parses a positive integer written in ASCII digits.
+///
+/// # Returns
+/// A new `Parser` that produces a `usize`.
+///
+/// # Examples
+/// ```
+/// use aoc_utils::parser::*;
+///
+/// assert_eq!(bytes::number().parse(b"123,4".as_slice()), Some((123, b",4".as_slice())));
+/// assert_eq!(bytes::number().run_parser(b"x".as_slice()), None);
+/// ```
+pub fn number() -> ParserImpl<Number> {
+    ParserImpl(Number {})
+}
+
+impl<'a> Parser<'a, [u8]> for Number {
+    type Output = usize;
+
+    fn parse(&self, input: &'a [u8]) -> Option<(Self::Output, &'a [u8])> {
+        let fst_non_digit = input
+            .iter()
+            .position(|b| !b.is_ascii_digit())
+            .unwrap_or(input.len());
+
+        if fst_non_digit == 0 {
+            return None;
+        }
+
+        input[..fst_non_digit]
+            .iter()
+            .try_fold(0usize, |n, b| {
+                n.checked_mul(10)?.checked_add(usize::from(b - b'0'))
+            })
+            .map(|n| (n, &input[fst_non_digit..]))
+    }
+
+    fn describe_into(&self, _rules: &mut Rules) -> Ebnf {
+        Ebnf::class(|c| c.is_ascii_digit()).postfix("+")
+    }
+}
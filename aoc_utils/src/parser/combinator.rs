@@ -1,6 +1,16 @@
-use super::Parser;
+use super::{
+    Ebnf, Parser, ParserImpl, Rules,
+    context::Context,
+    input::{Input, address, consumed},
+    spaces,
+};
 
-use std::ops::{Bound, RangeBounds};
+use std::{
+    cell::{OnceCell, RefCell},
+    collections::HashMap,
+    ops::{Bound, RangeBounds},
+    rc::{Rc, Weak},
+};
 
 ////////////////////////////////////////////////////
 //                    Repeated                    //
@@ -13,10 +23,12 @@ pub struct Repeat<P, R> {
     pub(super) range: R,
 }
 
-impl<P: Parser, R: RangeBounds<usize>> Parser for Repeat<P, R> {
+impl<'a, I: Input + ?Sized, P: Parser<'a, I>, R: RangeBounds<usize>> Parser<'a, I>
+    for Repeat<P, R>
+{
     type Output = Vec<P::Output>;
 
-    fn parse<'a>(&self, mut input: &'a str) -> Option<(Self::Output, &'a str)> {
+    fn parse(&self, mut input: &'a I) -> Option<(Self::Output, &'a I)> {
         let min = match self.range.start_bound() {
             Bound::Included(&n) => n,
             Bound::Excluded(&n) => n + 1,
@@ -42,6 +54,10 @@ impl<P: Parser, R: RangeBounds<usize>> Parser for Repeat<P, R> {
 
         (count >= min).then_some((items, input))
     }
+
+    fn describe_into(&self, rules: &mut Rules) -> Ebnf {
+        self.parser.describe_into(rules).repeat(&self.range)
+    }
 }
 
 /////////////////////////////////////////////////
@@ -56,10 +72,12 @@ pub struct SepBy<P, Q, R> {
     pub(super) range: R,
 }
 
-impl<P: Parser, Q: Parser, R: RangeBounds<usize>> Parser for SepBy<P, Q, R> {
+impl<'a, I: Input + ?Sized, P: Parser<'a, I>, Q: Parser<'a, I>, R: RangeBounds<usize>> Parser<'a, I>
+    for SepBy<P, Q, R>
+{
     type Output = Vec<P::Output>;
 
-    fn parse<'a>(&self, input: &'a str) -> Option<(Self::Output, &'a str)> {
+    fn parse(&self, input: &'a I) -> Option<(Self::Output, &'a I)> {
         let min = match self.range.start_bound() {
             Bound::Included(&n) => n,
             Bound::Excluded(&n) => n + 1,
@@ -95,6 +113,12 @@ impl<P: Parser, Q: Parser, R: RangeBounds<usize>> Parser for SepBy<P, Q, R> {
 
         (count >= min).then_some((results, s))
     }
+
+    fn describe_into(&self, rules: &mut Rules) -> Ebnf {
+        let item = self.parser.describe_into(rules);
+        let separator = self.rhs.describe_into(rules);
+        item.separated_by(separator, &self.range)
+    }
 }
 
 ///////////////////////////////////////////////
@@ -108,14 +132,98 @@ pub struct Map<P, F> {
     pub(super) f: F,
 }
 
-impl<U, P: Parser, F: Fn(P::Output) -> U> Parser for Map<P, F> {
+impl<'a, I: Input + ?Sized, U, P: Parser<'a, I>, F: Fn(P::Output) -> U> Parser<'a, I>
+    for Map<P, F>
+{
     type Output = U;
 
-    fn parse<'a>(&self, s: &'a str) -> Option<(Self::Output, &'a str)> {
+    fn parse(&self, s: &'a I) -> Option<(Self::Output, &'a I)> {
         self.parser
             .parse(s)
             .map(|(output, remaining)| ((self.f)(output), remaining))
     }
+
+    fn describe_into(&self, rules: &mut Rules) -> Ebnf {
+        self.parser.describe_into(rules)
+    }
+}
+
+/// Value that may hold the result of a fallible computation, like `Option` or `Result`.
+pub trait Fallible {
+    /// The type of the successful value.
+    type Value;
+
+    /// Converts into an `Option`, discarding any error.
+    fn into_option(self) -> Option<Self::Value>;
+}
+
+impl<T> Fallible for Option<T> {
+    type Value = T;
+
+    fn into_option(self) -> Option<T> {
+        self
+    }
+}
+
+impl<T, E> Fallible for Result<T, E> {
+    type Value = T;
+
+    fn into_option(self) -> Option<T> {
+        self.ok()
+    }
+}
+
+//////////////////////////////////////////////////
+//                    TryMap                    //
+//////////////////////////////////////////////////
+
+/// Parser that transforms the output of another parser using a function that may fail.
+#[derive(Clone, Copy)]
+pub struct TryMap<P, F> {
+    pub(super) parser: P,
+    pub(super) f: F,
+}
+
+impl<'a, I: Input + ?Sized, U: Fallible, P: Parser<'a, I>, F: Fn(P::Output) -> U> Parser<'a, I>
+    for TryMap<P, F>
+{
+    type Output = U::Value;
+
+    fn parse(&self, s: &'a I) -> Option<(Self::Output, &'a I)> {
+        let (output, remaining) = self.parser.parse(s)?;
+        Some(((self.f)(output).into_option()?, remaining))
+    }
+
+    fn describe_into(&self, rules: &mut Rules) -> Ebnf {
+        self.parser.describe_into(rules)
+    }
+}
+
+//////////////////////////////////////////////////
+//                    Verify                    //
+//////////////////////////////////////////////////
+
+/// Parser that fails if the output of another parser doesn't satisfy a predicate.
+#[derive(Clone, Copy)]
+pub struct Verify<P, F> {
+    pub(super) parser: P,
+    pub(super) pred: F,
+}
+
+impl<'a, I: Input + ?Sized, P: Parser<'a, I>, F: Fn(&P::Output) -> bool> Parser<'a, I>
+    for Verify<P, F>
+{
+    type Output = P::Output;
+
+    fn parse(&self, s: &'a I) -> Option<(Self::Output, &'a I)> {
+        self.parser
+            .parse(s)
+            .filter(|(output, _)| (self.pred)(output))
+    }
+
+    fn describe_into(&self, rules: &mut Rules) -> Ebnf {
+        self.parser.describe_into(rules)
+    }
 }
 
 //////////////////////////////////////////////
@@ -129,16 +237,20 @@ pub struct Or<P, Q> {
     pub(super) rhs: Q,
 }
 
-impl<P, Q> Parser for Or<P, Q>
+impl<'a, I: Input + ?Sized, P, Q> Parser<'a, I> for Or<P, Q>
 where
-    P: Parser,
-    Q: Parser<Output = P::Output>,
+    P: Parser<'a, I>,
+    Q: Parser<'a, I, Output = P::Output>,
 {
     type Output = P::Output;
 
-    fn parse<'a>(&self, s: &'a str) -> Option<(Self::Output, &'a str)> {
+    fn parse(&self, s: &'a I) -> Option<(Self::Output, &'a I)> {
         self.lhs.parse(s).or_else(|| self.rhs.parse(s))
     }
+
+    fn describe_into(&self, rules: &mut Rules) -> Ebnf {
+        Ebnf::alternative([self.lhs.describe_into(rules), self.rhs.describe_into(rules)])
+    }
 }
 
 ///////////////////////////////////////////////////////
@@ -152,14 +264,23 @@ pub struct IgnoredThen<P, Q> {
     pub(super) rhs: Q,
 }
 
-impl<P: Parser, Q: Parser> Parser for IgnoredThen<P, Q> {
+impl<'a, I: Input + ?Sized, P: Parser<'a, I>, Q: Parser<'a, I>> Parser<'a, I>
+    for IgnoredThen<P, Q>
+{
     type Output = Q::Output;
 
-    fn parse<'a>(&self, s: &'a str) -> Option<(Self::Output, &'a str)> {
+    fn parse(&self, s: &'a I) -> Option<(Self::Output, &'a I)> {
         self.parser
             .parse(s)
             .and_then(|(_, remaining)| self.rhs.parse(remaining))
     }
+
+    fn describe_into(&self, rules: &mut Rules) -> Ebnf {
+        Ebnf::sequence([
+            self.parser.describe_into(rules),
+            self.rhs.describe_into(rules),
+        ])
+    }
 }
 
 //////////////////////////////////////////////////////
@@ -173,16 +294,23 @@ pub struct ThenIgnore<P, Q> {
     pub(super) rhs: Q,
 }
 
-impl<P: Parser, Q: Parser> Parser for ThenIgnore<P, Q> {
+impl<'a, I: Input + ?Sized, P: Parser<'a, I>, Q: Parser<'a, I>> Parser<'a, I> for ThenIgnore<P, Q> {
     type Output = P::Output;
 
-    fn parse<'a>(&self, s: &'a str) -> Option<(Self::Output, &'a str)> {
+    fn parse(&self, s: &'a I) -> Option<(Self::Output, &'a I)> {
         self.parser.parse(s).and_then(|(output, remaining)| {
             self.rhs
                 .parse(remaining)
                 .map(|(_, remaining)| (output, remaining))
         })
     }
+
+    fn describe_into(&self, rules: &mut Rules) -> Ebnf {
+        Ebnf::sequence([
+            self.parser.describe_into(rules),
+            self.rhs.describe_into(rules),
+        ])
+    }
 }
 
 ///////////////////////////////////////////////////
@@ -196,16 +324,23 @@ pub struct ThenZip<P, Q> {
     pub(super) rhs: Q,
 }
 
-impl<P: Parser, Q: Parser> Parser for ThenZip<P, Q> {
+impl<'a, I: Input + ?Sized, P: Parser<'a, I>, Q: Parser<'a, I>> Parser<'a, I> for ThenZip<P, Q> {
     type Output = (P::Output, Q::Output);
 
-    fn parse<'a>(&self, s: &'a str) -> Option<(Self::Output, &'a str)> {
+    fn parse(&self, s: &'a I) -> Option<(Self::Output, &'a I)> {
         self.parser.parse(s).and_then(|(left, remaining)| {
             self.rhs
                 .parse(remaining)
                 .map(|(right, remaining)| ((left, right), remaining))
         })
     }
+
+    fn describe_into(&self, rules: &mut Rules) -> Ebnf {
+        Ebnf::sequence([
+            self.parser.describe_into(rules),
+            self.rhs.describe_into(rules),
+        ])
+    }
 }
 
 ///////////////////////////////////////////////////////
@@ -220,16 +355,25 @@ pub struct ThenZipWith<P, Q, F> {
     pub(super) f: F,
 }
 
-impl<V, P: Parser, Q: Parser, F: Fn(P::Output, Q::Output) -> V> Parser for ThenZipWith<P, Q, F> {
+impl<'a, I: Input + ?Sized, V, P: Parser<'a, I>, Q: Parser<'a, I>, F: Fn(P::Output, Q::Output) -> V>
+    Parser<'a, I> for ThenZipWith<P, Q, F>
+{
     type Output = V;
 
-    fn parse<'a>(&self, s: &'a str) -> Option<(Self::Output, &'a str)> {
+    fn parse(&self, s: &'a I) -> Option<(Self::Output, &'a I)> {
         self.parser.parse(s).and_then(|(left, remaining)| {
             self.rhs
                 .parse(remaining)
                 .map(|(right, remaining)| ((self.f)(left, right), remaining))
         })
     }
+
+    fn describe_into(&self, rules: &mut Rules) -> Ebnf {
+        Ebnf::sequence([
+            self.parser.describe_into(rules),
+            self.rhs.describe_into(rules),
+        ])
+    }
 }
 
 ///////////////////////////////////////////////////
@@ -243,14 +387,20 @@ pub struct AndThen<P, F> {
     pub(super) f: F,
 }
 
-impl<P: Parser, Q: Parser, F: Fn(P::Output) -> Q> Parser for AndThen<P, F> {
+impl<'a, I: Input + ?Sized, P: Parser<'a, I>, Q: Parser<'a, I>, F: Fn(P::Output) -> Q> Parser<'a, I>
+    for AndThen<P, F>
+{
     type Output = Q::Output;
 
-    fn parse<'a>(&self, input: &'a str) -> Option<(Self::Output, &'a str)> {
+    fn parse(&self, input: &'a I) -> Option<(Self::Output, &'a I)> {
         self.parser
             .parse(input)
             .and_then(|(x, remaining)| (self.f)(x).parse(remaining))
     }
+
+    fn describe_into(&self, rules: &mut Rules) -> Ebnf {
+        Ebnf::sequence([self.parser.describe_into(rules), Ebnf::special("and_then")])
+    }
 }
 
 /////////////////////////////////////////////////////
@@ -263,12 +413,16 @@ pub struct LookAhead<P> {
     pub(super) parser: P,
 }
 
-impl<P: Parser> Parser for LookAhead<P> {
+impl<'a, I: Input + ?Sized, P: Parser<'a, I>> Parser<'a, I> for LookAhead<P> {
     type Output = P::Output;
 
-    fn parse<'a>(&self, input: &'a str) -> Option<(Self::Output, &'a str)> {
+    fn parse(&self, input: &'a I) -> Option<(Self::Output, &'a I)> {
         self.parser.parse(input).map(|(output, _)| (output, input))
     }
+
+    fn describe_into(&self, rules: &mut Rules) -> Ebnf {
+        self.parser.describe_into(rules).prefix("&")
+    }
 }
 
 ////////////////////////////////////////////////////
@@ -281,16 +435,715 @@ pub struct Anywhere<P> {
     pub(super) parser: P,
 }
 
-impl<P: Parser> Parser for Anywhere<P> {
+impl<'a, I: Input + ?Sized, P: Parser<'a, I>> Parser<'a, I> for Anywhere<P> {
     type Output = P::Output;
 
-    fn parse<'a>(&self, input: &'a str) -> Option<(Self::Output, &'a str)> {
+    fn parse(&self, input: &'a I) -> Option<(Self::Output, &'a I)> {
         match self.parser.parse(input) {
-            None => match input {
-                "" => None,
-                other => self.parse(&other[1..]),
-            },
+            None => self.parse(input.split_at(input.first_len()?).1),
             ok => ok,
         }
     }
+
+    fn describe_into(&self, rules: &mut Rules) -> Ebnf {
+        Ebnf::sequence([
+            Ebnf::special("any").postfix("*"),
+            self.parser.describe_into(rules),
+        ])
+    }
+}
+
+/////////////////////////////////////////////////////
+//                    Recognize                    //
+/////////////////////////////////////////////////////
+
+/// Parser that returns the slice of input consumed by another parser.
+#[derive(Clone, Copy)]
+pub struct Recognize<P> {
+    pub(super) parser: P,
+}
+
+impl<'a, I: Input + ?Sized + 'a, P: Parser<'a, I>> Parser<'a, I> for Recognize<P> {
+    type Output = &'a I;
+
+    fn parse(&self, input: &'a I) -> Option<(Self::Output, &'a I)> {
+        self.parser
+            .parse(input)
+            .map(|(_, remaining)| (input.split_at(consumed(input, remaining)).0, remaining))
+    }
+
+    fn describe_into(&self, rules: &mut Rules) -> Ebnf {
+        self.parser.describe_into(rules)
+    }
+}
+
+////////////////////////////////////////////////////
+//                    Optional                    //
+////////////////////////////////////////////////////
+
+/// Parser that optionally parses another parser.
+#[derive(Clone, Copy)]
+pub struct Optional<P> {
+    pub(super) parser: P,
+}
+
+impl<'a, I: Input + ?Sized, P: Parser<'a, I>> Parser<'a, I> for Optional<P> {
+    type Output = Option<P::Output>;
+
+    fn parse(&self, input: &'a I) -> Option<(Self::Output, &'a I)> {
+        match self.parser.parse(input) {
+            Some((output, remaining)) => Some((Some(output), remaining)),
+            None => Some((None, input)),
+        }
+    }
+
+    fn describe_into(&self, rules: &mut Rules) -> Ebnf {
+        self.parser.describe_into(rules).postfix("?")
+    }
+}
+
+///////////////////////////////////////////////////
+//                    Between                    //
+///////////////////////////////////////////////////
+
+/// Parser that parses another parser surrounded by an opening and a closing parser.
+#[derive(Clone, Copy)]
+pub struct Between<P, Q, S> {
+    pub(super) parser: P,
+    pub(super) open: Q,
+    pub(super) close: S,
+}
+
+impl<'a, I: Input + ?Sized, P: Parser<'a, I>, Q: Parser<'a, I>, S: Parser<'a, I>> Parser<'a, I>
+    for Between<P, Q, S>
+{
+    type Output = P::Output;
+
+    fn parse(&self, input: &'a I) -> Option<(Self::Output, &'a I)> {
+        let (_, remaining) = self.open.parse(input)?;
+        let (output, remaining) = self.parser.parse(remaining)?;
+        let (_, remaining) = self.close.parse(remaining)?;
+        Some((output, remaining))
+    }
+
+    fn describe_into(&self, rules: &mut Rules) -> Ebnf {
+        Ebnf::sequence([
+            self.open.describe_into(rules),
+            self.parser.describe_into(rules),
+            self.close.describe_into(rules),
+        ])
+    }
+}
+
+////////////////////////////////////////////////////
+//                    ManyTill                    //
+////////////////////////////////////////////////////
+
+/// Parser that parses occurrences of a parser until another one succeeds.
+#[derive(Clone, Copy)]
+pub struct ManyTill<P, Q> {
+    pub(super) parser: P,
+    pub(super) end: Q,
+}
+
+impl<'a, I: Input + ?Sized, P: Parser<'a, I>, Q: Parser<'a, I>> Parser<'a, I> for ManyTill<P, Q> {
+    type Output = Vec<P::Output>;
+
+    fn parse(&self, mut input: &'a I) -> Option<(Self::Output, &'a I)> {
+        let mut items = vec![];
+
+        loop {
+            if let Some((_, remaining)) = self.end.parse(input) {
+                return Some((items, remaining));
+            }
+
+            let (output, remaining) = self.parser.parse(input)?;
+            if remaining.len() == input.len() {
+                return None;
+            }
+
+            items.push(output);
+            input = remaining;
+        }
+    }
+
+    fn describe_into(&self, rules: &mut Rules) -> Ebnf {
+        Ebnf::sequence([
+            self.parser.describe_into(rules).postfix("*"),
+            self.end.describe_into(rules),
+        ])
+    }
+}
+
+////////////////////////////////////////////////////
+//                    SepEndBy                    //
+////////////////////////////////////////////////////
+
+/// Parser that parses a range of occurrences separated and optionally ended by another parser.
+#[derive(Clone, Copy)]
+pub struct SepEndBy<P, Q, R> {
+    pub(super) sep_by: SepBy<P, Q, R>,
+}
+
+impl<'a, I: Input + ?Sized, P: Parser<'a, I>, Q: Parser<'a, I>, R: RangeBounds<usize>> Parser<'a, I>
+    for SepEndBy<P, Q, R>
+{
+    type Output = Vec<P::Output>;
+
+    fn parse(&self, input: &'a I) -> Option<(Self::Output, &'a I)> {
+        let (items, remaining) = self.sep_by.parse(input)?;
+
+        match self.sep_by.rhs.parse(remaining) {
+            Some((_, rem)) if !items.is_empty() => Some((items, rem)),
+            _ => Some((items, remaining)),
+        }
+    }
+
+    fn describe_into(&self, rules: &mut Rules) -> Ebnf {
+        Ebnf::sequence([
+            self.sep_by.describe_into(rules),
+            self.sep_by.rhs.describe_into(rules).postfix("?"),
+        ])
+    }
+}
+
+////////////////////////////////////////////////////
+//                    SkipMany                    //
+////////////////////////////////////////////////////
+
+/// Parser that discards any number of occurrences of another parser.
+#[derive(Clone, Copy)]
+pub struct SkipMany<P> {
+    pub(super) parser: P,
+}
+
+impl<'a, I: Input + ?Sized, P: Parser<'a, I>> Parser<'a, I> for SkipMany<P> {
+    type Output = ();
+
+    fn parse(&self, mut input: &'a I) -> Option<(Self::Output, &'a I)> {
+        while let Some((_, remaining)) = self.parser.parse(input)
+            && remaining.len() < input.len()
+        {
+            input = remaining;
+        }
+
+        Some(((), input))
+    }
+
+    fn describe_into(&self, rules: &mut Rules) -> Ebnf {
+        self.parser.describe_into(rules).postfix("*")
+    }
+}
+
+/////////////////////////////////////////////////////////
+//                    NotFollowedBy                    //
+/////////////////////////////////////////////////////////
+
+/// Parser that succeeds only if another parser fails, without consuming any input.
+#[derive(Clone, Copy)]
+pub struct NotFollowedBy<P> {
+    pub(super) parser: P,
+}
+
+/// Creates a parser that succeeds only if `parser` fails, without consuming any input.
+///
+/// # Arguments
+/// * `parser`: The parser that must not succeed.
+///
+/// # Returns
+/// A new `Parser` that produces `()`.
+///
+/// # Examples
+/// ```
+/// use aoc_utils::parser::*;
+///
+/// let keyword = prefix("let") << not_followed_by(alnum());
+///
+/// assert_eq!(keyword.parse("let x"), Some(("let", " x")));
+/// assert_eq!(keyword.parse("letter"), None);
+/// ```
+pub fn not_followed_by<'a, I: Input + ?Sized, P: Parser<'a, I>>(
+    parser: P,
+) -> ParserImpl<NotFollowedBy<P>> {
+    ParserImpl(NotFollowedBy { parser })
+}
+
+impl<'a, I: Input + ?Sized, P: Parser<'a, I>> Parser<'a, I> for NotFollowedBy<P> {
+    type Output = ();
+
+    fn parse(&self, input: &'a I) -> Option<(Self::Output, &'a I)> {
+        self.parser.parse(input).is_none().then_some(((), input))
+    }
+
+    fn describe_into(&self, rules: &mut Rules) -> Ebnf {
+        self.parser.describe_into(rules).prefix("!")
+    }
+}
+
+//////////////////////////////////////////////////
+//                    Choice                    //
+//////////////////////////////////////////////////
+
+/// Parser that tries a list of parsers in order and returns the output of the first one that succeeds.
+#[derive(Clone)]
+pub struct Choice<P> {
+    pub(super) parsers: Vec<P>,
+}
+
+/// Creates a parser that tries each parser in order and returns the output of the first one that succeeds.
+///
+/// Unlike chaining `|`, the list can be built at runtime but all the parsers must have the same type.
+///
+/// # Arguments
+/// * `parsers`: The alternatives to try.
+///
+/// # Returns
+/// A new `Parser` that produces the output of the first parser that succeeds.
+///
+/// # Examples
+/// ```
+/// use aoc_utils::parser::*;
+///
+/// let direction = choice(["up", "down", "left", "right"].map(prefix));
+///
+/// assert_eq!(direction.run_parser("left 5"), Some("left"));
+/// assert_eq!(direction.run_parser("forward 5"), None);
+/// ```
+pub fn choice<'a, I: Input + ?Sized, P: Parser<'a, I>>(
+    parsers: impl IntoIterator<Item = P>,
+) -> ParserImpl<Choice<P>> {
+    ParserImpl(Choice {
+        parsers: parsers.into_iter().collect(),
+    })
+}
+
+impl<'a, I: Input + ?Sized, P: Parser<'a, I>> Parser<'a, I> for Choice<P> {
+    type Output = P::Output;
+
+    fn parse(&self, input: &'a I) -> Option<(Self::Output, &'a I)> {
+        self.parsers.iter().find_map(|p| p.parse(input))
+    }
+
+    fn describe_into(&self, rules: &mut Rules) -> Ebnf {
+        Ebnf::alternative(
+            self.parsers
+                .iter()
+                .map(|p| p.describe_into(rules))
+                .collect::<Vec<_>>(),
+        )
+    }
+}
+
+/////////////////////////////////////////////////
+//                    Value                    //
+/////////////////////////////////////////////////
+
+/// Parser that replaces the output of another parser with a fixed value.
+#[derive(Clone, Copy)]
+pub struct Value<P, T> {
+    pub(super) parser: P,
+    pub(super) value: T,
+}
+
+impl<'a, I: Input + ?Sized, P: Parser<'a, I>, T: Clone> Parser<'a, I> for Value<P, T> {
+    type Output = T;
+
+    fn parse(&self, input: &'a I) -> Option<(Self::Output, &'a I)> {
+        self.parser
+            .parse(input)
+            .map(|(_, remaining)| (self.value.clone(), remaining))
+    }
+
+    fn describe_into(&self, rules: &mut Rules) -> Ebnf {
+        self.parser.describe_into(rules)
+    }
+}
+
+//////////////////////////////////////////////////
+//                    Lexeme                    //
+//////////////////////////////////////////////////
+
+/// Parser that runs another parser and then discards the spaces after it.
+#[derive(Clone, Copy)]
+pub struct Lexeme<P> {
+    pub(super) parser: P,
+}
+
+impl<'a, P: Parser<'a>> Parser<'a> for Lexeme<P> {
+    type Output = P::Output;
+
+    fn parse(&self, input: &'a str) -> Option<(Self::Output, &'a str)> {
+        let (output, remaining) = self.parser.parse(input)?;
+        let (_, remaining) = spaces().parse(remaining)?;
+        Some((output, remaining))
+    }
+
+    fn describe_into(&self, rules: &mut Rules) -> Ebnf {
+        Ebnf::sequence([
+            self.parser.describe_into(rules),
+            spaces().describe_into(rules),
+        ])
+    }
+}
+
+///////////////////////////////////////////////////
+//                    ChainL1                    //
+///////////////////////////////////////////////////
+
+/// Parser that parses one or more occurrences of a parser separated by an operator, combining them from the left.
+#[derive(Clone, Copy)]
+pub struct ChainL1<P, Q, F> {
+    pub(super) parser: P,
+    pub(super) op: Q,
+    pub(super) f: F,
+}
+
+impl<'a, I: Input + ?Sized, P, Q, F> Parser<'a, I> for ChainL1<P, Q, F>
+where
+    P: Parser<'a, I>,
+    Q: Parser<'a, I>,
+    F: Fn(P::Output, Q::Output, P::Output) -> P::Output,
+{
+    type Output = P::Output;
+
+    fn parse(&self, input: &'a I) -> Option<(Self::Output, &'a I)> {
+        let (mut acc, mut input) = self.parser.parse(input)?;
+
+        while let Some((op, rem)) = self.op.parse(input)
+            && let Some((rhs, rem)) = self.parser.parse(rem)
+        {
+            acc = (self.f)(acc, op, rhs);
+            input = rem;
+        }
+
+        Some((acc, input))
+    }
+
+    fn describe_into(&self, rules: &mut Rules) -> Ebnf {
+        let operand = self.parser.describe_into(rules);
+        let op = self.op.describe_into(rules);
+        Ebnf::sequence([operand.clone(), Ebnf::sequence([op, operand]).postfix("*")])
+    }
+}
+
+///////////////////////////////////////////////////
+//                    ChainR1                    //
+///////////////////////////////////////////////////
+
+/// Parser that parses one or more occurrences of a parser separated by an operator, combining them from the right.
+#[derive(Clone, Copy)]
+pub struct ChainR1<P, Q, F> {
+    pub(super) parser: P,
+    pub(super) op: Q,
+    pub(super) f: F,
+}
+
+impl<'a, I: Input + ?Sized, P, Q, F> Parser<'a, I> for ChainR1<P, Q, F>
+where
+    P: Parser<'a, I>,
+    Q: Parser<'a, I>,
+    F: Fn(P::Output, Q::Output, P::Output) -> P::Output,
+{
+    type Output = P::Output;
+
+    fn parse(&self, input: &'a I) -> Option<(Self::Output, &'a I)> {
+        let (first, mut input) = self.parser.parse(input)?;
+        let mut operands = vec![first];
+        let mut ops = vec![];
+
+        while let Some((op, rem)) = self.op.parse(input)
+            && let Some((rhs, rem)) = self.parser.parse(rem)
+        {
+            ops.push(op);
+            operands.push(rhs);
+            input = rem;
+        }
+
+        let mut acc = operands.pop()?;
+        while let (Some(op), Some(lhs)) = (ops.pop(), operands.pop()) {
+            acc = (self.f)(lhs, op, acc);
+        }
+
+        Some((acc, input))
+    }
+
+    fn describe_into(&self, rules: &mut Rules) -> Ebnf {
+        let operand = self.parser.describe_into(rules);
+        let op = self.op.describe_into(rules);
+        Ebnf::sequence([operand.clone(), Ebnf::sequence([op, operand]).postfix("*")])
+    }
+}
+
+////////////////////////////////////////////////
+//                    Memo                    //
+////////////////////////////////////////////////
+
+/// Results cached by a `Memo` during a single run, keyed by the address and length of the input.
+pub(super) struct MemoCache<T> {
+    run: Option<Context>,
+    entries: HashMap<(usize, usize), Option<(T, usize)>>,
+}
+
+impl<T> Default for MemoCache<T> {
+    fn default() -> Self {
+        MemoCache {
+            run: None,
+            entries: HashMap::new(),
+        }
+    }
+}
+
+/// Parser that caches the results of another parser by position in the input.
+pub struct Memo<P, T> {
+    pub(super) parser: P,
+    pub(super) cache: Rc<RefCell<MemoCache<T>>>,
+}
+
+impl<P: Clone, T> Clone for Memo<P, T> {
+    fn clone(&self) -> Self {
+        Memo {
+            parser: self.parser.clone(),
+            cache: Rc::clone(&self.cache),
+        }
+    }
+}
+
+impl<'a, I: Input + ?Sized, P: Parser<'a, I, Output = T>, T: Clone> Parser<'a, I> for Memo<P, T> {
+    type Output = T;
+
+    fn parse(&self, input: &'a I) -> Option<(Self::Output, &'a I)> {
+        let Some(context) = Context::current() else {
+            return self.parser.parse(input);
+        };
+
+        let key = (address(input), input.len());
+
+        {
+            let cache = self.cache.borrow();
+            if cache.run == Some(context)
+                && let Some(entry) = cache.entries.get(&key)
+            {
+                return entry
+                    .clone()
+                    .map(|(output, consumed)| (output, input.split_at(consumed).1));
+            }
+        }
+
+        let result = self.parser.parse(input);
+
+        let mut cache = self.cache.borrow_mut();
+        if cache.run != Some(context) {
+            cache.run = Some(context);
+            cache.entries.clear();
+        }
+        cache.entries.insert(
+            key,
+            result
+                .as_ref()
+                .map(|(output, remaining)| (output.clone(), consumed(input, remaining))),
+        );
+
+        result
+    }
+
+    fn describe_into(&self, rules: &mut Rules) -> Ebnf {
+        self.parser.describe_into(rules)
+    }
+}
+
+/////////////////////////////////////////////////
+//                    Boxed                    //
+/////////////////////////////////////////////////
+
+/// Parser that hides the type of another parser behind a shared pointer.
+pub struct Boxed<'a, T, I: Input + ?Sized = str>(pub(super) Rc<dyn Parser<'a, I, Output = T> + 'a>);
+
+/// A type-erased parser.
+///
+/// Parsers of different types with the same output become the same type once boxed,
+/// so they can be stored in collections or returned from different branches.
+pub type BoxedParser<'a, T, I = str> = ParserImpl<Boxed<'a, T, I>>;
+
+impl<T, I: Input + ?Sized> Clone for Boxed<'_, T, I> {
+    fn clone(&self) -> Self {
+        Boxed(Rc::clone(&self.0))
+    }
+}
+
+impl<'a, I: Input + ?Sized, T> Parser<'a, I> for Boxed<'a, T, I> {
+    type Output = T;
+
+    fn parse(&self, input: &'a I) -> Option<(Self::Output, &'a I)> {
+        self.0.parse(input)
+    }
+
+    fn describe_into(&self, rules: &mut Rules) -> Ebnf {
+        self.0.describe_into(rules)
+    }
+}
+
+/////////////////////////////////////////////////////
+//                    Recursive                    //
+/////////////////////////////////////////////////////
+
+type RecursiveCell<'a, T, I> = OnceCell<BoxedParser<'a, T, I>>;
+
+enum RecursiveRef<'a, T, I: Input + ?Sized> {
+    Owned(Rc<RecursiveCell<'a, T, I>>),
+    Weak(Weak<RecursiveCell<'a, T, I>>),
+}
+
+/// Parser that can refer to itself.
+pub struct Recursive<'a, T, I: Input + ?Sized = str> {
+    cell: RecursiveRef<'a, T, I>,
+}
+
+/// Creates a parser that can refer to itself.
+///
+/// `f` receives a handle to the parser being defined and returns its definition.
+/// The handle can be cloned and used anywhere inside the definition, but it must not outlive
+/// the returned parser.
+///
+/// # Arguments
+/// * `f`: A closure that takes a handle to the parser and returns its definition.
+///
+/// # Returns
+/// A new `Parser` that produces the output of the definition.
+///
+/// # Examples
+/// ```
+/// use aoc_utils::parser::*;
+///
+/// #[derive(Debug, PartialEq, Eq)]
+/// enum Nested {
+///     Single(usize),
+///     Multiple(Vec<Nested>),
+/// }
+///
+/// use Nested::*;
+///
+/// let nested = recursive(|nested| {
+///     number().map(Single).or(nested
+///         .sep_by(prefix(","), ..)
+///         .between(prefix("["), prefix("]"))
+///         .map(Multiple))
+/// });
+///
+/// assert_eq!(
+///     nested.run_parser("[1,[2,[]]]"),
+///     Some(Multiple(vec![Single(1), Multiple(vec![Single(2), Multiple(vec![])])]))
+/// );
+/// ```
+pub fn recursive<'a, I, T, P, F>(f: F) -> ParserImpl<Recursive<'a, T, I>>
+where
+    I: Input + ?Sized,
+    P: Parser<'a, I, Output = T> + 'a,
+    F: FnOnce(ParserImpl<Recursive<'a, T, I>>) -> P,
+{
+    let cell = Rc::new(OnceCell::new());
+    let handle = ParserImpl(Recursive {
+        cell: RecursiveRef::Weak(Rc::downgrade(&cell)),
+    });
+
+    let _ = cell.set(f(handle).boxed());
+
+    ParserImpl(Recursive {
+        cell: RecursiveRef::Owned(cell),
+    })
+}
+
+impl<T, I: Input + ?Sized> Clone for Recursive<'_, T, I> {
+    fn clone(&self) -> Self {
+        let cell = match &self.cell {
+            RecursiveRef::Owned(cell) => RecursiveRef::Owned(Rc::clone(cell)),
+            RecursiveRef::Weak(cell) => RecursiveRef::Weak(Weak::clone(cell)),
+        };
+
+        Recursive { cell }
+    }
+}
+
+impl<'a, I: Input + ?Sized, T> Recursive<'a, T, I> {
+    fn cell(&self) -> Rc<RecursiveCell<'a, T, I>> {
+        match &self.cell {
+            RecursiveRef::Owned(cell) => Rc::clone(cell),
+            RecursiveRef::Weak(cell) => cell
+                .upgrade()
+                .expect("Recursive parser handle used after the parser was dropped"),
+        }
+    }
+}
+
+impl<'a, I: Input + ?Sized, T> Parser<'a, I> for Recursive<'a, T, I> {
+    type Output = T;
+
+    fn parse(&self, input: &'a I) -> Option<(Self::Output, &'a I)> {
+        self.cell()
+            .get()
+            .expect("Recursive parser used before being defined")
+            .parse(input)
+    }
+
+    fn describe_into(&self, rules: &mut Rules) -> Ebnf {
+        let cell = self.cell();
+
+        rules.recursive(Rc::as_ptr(&cell) as usize, |rules| match cell.get() {
+            Some(parser) => parser.describe_into(rules),
+            None => Ebnf::special("undefined"),
+        })
+    }
+}
+
+//////////////////////////////////////////////////
+//                    Tuples                    //
+//////////////////////////////////////////////////
+
+macro_rules! tuple_parser {
+    ($($(#[$attr:meta])* ($($P:ident $o:ident),+);)+) => {$(
+        $(#[$attr])*
+        impl<'a, I: Input + ?Sized, $($P: Parser<'a, I>),+> Parser<'a, I> for ($($P,)+) {
+            type Output = ($($P::Output,)+);
+
+            #[allow(non_snake_case)]
+            fn parse(&self, input: &'a I) -> Option<(Self::Output, &'a I)> {
+                let ($($P,)+) = self;
+                $(let ($o, input) = $P.parse(input)?;)+
+                Some((($($o,)+), input))
+            }
+
+            #[allow(non_snake_case)]
+            fn describe_into(&self, rules: &mut Rules) -> Ebnf {
+                let ($($P,)+) = self;
+                Ebnf::sequence([$($P.describe_into(rules)),+])
+            }
+        }
+    )+};
+}
+
+tuple_parser! {
+    /// Implementing `Parser` for tuples of parsers allows to parse sequences into flat tuples.
+    ///
+    /// The elements are parsed in order and the outputs are collected into a tuple of the same size.
+    /// Tuples of up to 12 parsers are supported.
+    ///
+    /// # Examples
+    /// ```
+    /// use aoc_utils::parser::*;
+    ///
+    /// let range = (number(), prefix("-"), number());
+    ///
+    /// assert_eq!(range.run_parser("1-3"), Some((1, "-", 3)));
+    /// assert_eq!(range.run_parser("1+3"), None);
+    /// ```
+    (P1 o1, P2 o2);
+    (P1 o1, P2 o2, P3 o3);
+    (P1 o1, P2 o2, P3 o3, P4 o4);
+    (P1 o1, P2 o2, P3 o3, P4 o4, P5 o5);
+    (P1 o1, P2 o2, P3 o3, P4 o4, P5 o5, P6 o6);
+    (P1 o1, P2 o2, P3 o3, P4 o4, P5 o5, P6 o6, P7 o7);
+    (P1 o1, P2 o2, P3 o3, P4 o4, P5 o5, P6 o6, P7 o7, P8 o8);
+    (P1 o1, P2 o2, P3 o3, P4 o4, P5 o5, P6 o6, P7 o7, P8 o8, P9 o9);
+    (P1 o1, P2 o2, P3 o3, P4 o4, P5 o5, P6 o6, P7 o7, P8 o8, P9 o9, P10 o10);
+    (P1 o1, P2 o2, P3 o3, P4 o4, P5 o5, P6 o6, P7 o7, P8 o8, P9 o9, P10 o10, P11 o11);
+    (P1 o1, P2 o2, P3 o3, P4 o4, P5 o5, P6 o6, P7 o7, P8 o8, P9 o9, P10 o10, P11 o11, P12 o12);
 }
@@ -0,0 +1,80 @@
+//! State shared by every parser taking part in a single run over an input.
+//!
+//! `Parser::run_parser` opens a new context, so combinators that need to know which run they belong to,
+//! like [`Memo`](super::Memo), can tell apart parses of different inputs. It also remembers where the
+//! input starts, so [`WithSpan`](super::WithSpan) can give positions relative to the whole input.
+//! A run over a part of the input of the run in progress stays in its context, which is how the
+//! helpers of [`input`](crate::input) give positions relative to the whole file.
+
+use super::input::{Input, address};
+
+use std::{cell::Cell, mem};
+
+/// Identifies a single run of a parser over an input.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub(crate) struct Context {
+    pub(crate) id: u64,
+    start: usize,
+    len: usize,
+    unit: usize,
+}
+
+thread_local! {
+    static CURRENT: Cell<Option<Context>> = const { Cell::new(None) };
+    static NEXT_ID: Cell<u64> = const { Cell::new(0) };
+}
+
+/// Restores the previous context when dropped, even if the parser panics.
+struct Restore(Option<Context>);
+
+impl Drop for Restore {
+    fn drop(&mut self) {
+        CURRENT.set(self.0);
+    }
+}
+
+impl Context {
+    /// Creates a context for a new run over `input`.
+    pub(crate) fn new<I: Input + ?Sized>(input: &I) -> Context {
+        let id = NEXT_ID.get();
+        NEXT_ID.set(id + 1);
+        Context {
+            id,
+            start: address(input),
+            len: input.len(),
+            unit: (mem::size_of_val(input) / input.len().max(1)).max(1),
+        }
+    }
+
+    /// Returns the context for a run over `input`: the one of the run in progress if `input` is
+    /// part of its input, or a new one.
+    pub(crate) fn for_run<I: Input + ?Sized>(input: &I) -> Context {
+        Context::current()
+            .filter(|context| context.offset_of(input).is_some())
+            .unwrap_or_else(|| Context::new(input))
+    }
+
+    /// Returns the context of the run in progress, if any.
+    pub(crate) fn current() -> Option<Context> {
+        CURRENT.get()
+    }
+
+    /// Returns the position of `s` from the start of the input of the run,
+    /// or `None` if `s` isn't part of it.
+    pub(crate) fn offset_of<I: Input + ?Sized>(&self, s: &I) -> Option<usize> {
+        let offset = address(s).checked_sub(self.start)? / self.unit;
+        (offset + s.len() <= self.len).then_some(offset)
+    }
+
+    /// Runs `f` inside this context.
+    pub(crate) fn enter<T>(self, f: impl FnOnce() -> T) -> T {
+        let _restore = Restore(CURRENT.replace(Some(self)));
+        f()
+    }
+}
+
+/// Runs `f` inside a new context over `input`, so the parsers run on parts of it get positions
+/// relative to the whole of it.
+pub(crate) fn within<I: Input + ?Sized, T>(input: &I, f: impl FnOnce() -> T) -> T {
+    Context::new(input).enter(f)
+}
@@ -0,0 +1,334 @@
+//! Rendering of parsers as EBNF-like grammars.
+
+use super::{Input, Parser};
+
+use std::{
+    fmt,
+    ops::{Bound, RangeBounds},
+};
+
+/// How tightly an expression binds, to know when it needs parentheses.
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Debug)]
+enum Precedence {
+    Alternative,
+    Sequence,
+    Atom,
+}
+
+/// EBNF-like text of a parser.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct Ebnf {
+    text: String,
+    precedence: Precedence,
+}
+
+impl Ebnf {
+    /// Creates an expression that never needs parentheses, like a literal or a rule name.
+    pub fn atom(text: impl Into<String>) -> Ebnf {
+        Ebnf {
+            text: text.into(),
+            precedence: Precedence::Atom,
+        }
+    }
+
+    /// Creates an expression for something the notation can't express, written as `<name>`.
+    pub fn special(name: &str) -> Ebnf {
+        Ebnf::atom(format!("<{name}>"))
+    }
+
+    /// Creates a literal string.
+    pub fn literal(s: &str) -> Ebnf {
+        Ebnf::atom(format!("{s:?}"))
+    }
+
+    /// Creates the class of the characters that satisfy `f`, looking only at ASCII characters.
+    pub fn class(f: impl Fn(char) -> bool) -> Ebnf {
+        let members = (0..128u8)
+            .map(char::from)
+            .filter(|&c| f(c))
+            .collect::<Vec<_>>();
+
+        match members[..] {
+            [] => Ebnf::special("char"),
+            [c] => Ebnf::literal(&c.to_string()),
+            _ if members.len() > 64 => {
+                let others = (0..128u8)
+                    .map(char::from)
+                    .filter(|&c| !f(c))
+                    .collect::<Vec<_>>();
+                Ebnf::atom(format!("[^{}]", class_ranges(&others)))
+            }
+            _ => Ebnf::atom(format!("[{}]", class_ranges(&members))),
+        }
+    }
+
+    /// Creates the sequence of `items`.
+    pub fn sequence(items: impl IntoIterator<Item = Ebnf>) -> Ebnf {
+        Ebnf::join(items, " ", Precedence::Sequence)
+    }
+
+    /// Creates the ordered choice between `items`.
+    pub fn alternative(items: impl IntoIterator<Item = Ebnf>) -> Ebnf {
+        Ebnf::join(items, " | ", Precedence::Alternative)
+    }
+
+    /// Appends a postfix operator, like `?` or `*`.
+    pub fn postfix(self, op: &str) -> Ebnf {
+        Ebnf::atom(format!("{}{op}", self.at(Precedence::Atom)))
+    }
+
+    /// Prepends a prefix operator, like `&` or `!`.
+    pub fn prefix(self, op: &str) -> Ebnf {
+        Ebnf::atom(format!("{op}{}", self.at(Precedence::Atom)))
+    }
+
+    /// Repeats the expression as many times as `range` allows.
+    pub fn repeat(self, range: &impl RangeBounds<usize>) -> Ebnf {
+        match bounds(range) {
+            (_, Some(0)) => Ebnf::literal(""),
+            (1, Some(1)) => self,
+            (0, Some(1)) => self.postfix("?"),
+            (0, None) => self.postfix("*"),
+            (1, None) => self.postfix("+"),
+            (min, None) => self.postfix(&format!("{{{min},}}")),
+            (min, Some(max)) if min == max => self.postfix(&format!("{{{min}}}")),
+            (min, Some(max)) => self.postfix(&format!("{{{min},{max}}}")),
+        }
+    }
+
+    /// Repeats the expression as many times as `range` allows, with `separator` between occurrences.
+    pub fn separated_by(self, separator: Ebnf, range: &impl RangeBounds<usize>) -> Ebnf {
+        let (min, max) = bounds(range);
+        let rest = (
+            Bound::Included(min.saturating_sub(1)),
+            max.map_or(Bound::Unbounded, |max| {
+                Bound::Included(max.saturating_sub(1))
+            }),
+        );
+
+        let items = Ebnf::sequence([
+            self.clone(),
+            Ebnf::sequence([separator, self]).repeat(&rest),
+        ]);
+
+        match (min, max) {
+            (_, Some(0)) => Ebnf::literal(""),
+            (0, _) => items.postfix("?"),
+            _ => items,
+        }
+    }
+
+    fn join(
+        items: impl IntoIterator<Item = Ebnf>,
+        separator: &str,
+        precedence: Precedence,
+    ) -> Ebnf {
+        let mut items = items.into_iter().collect::<Vec<_>>();
+
+        if items.len() == 1 {
+            return items.remove(0);
+        }
+
+        let text = items
+            .into_iter()
+            .map(|item| item.at(precedence))
+            .collect::<Vec<_>>()
+            .join(separator);
+
+        Ebnf { text, precedence }
+    }
+
+    /// Returns the text, in parentheses if it binds less tightly than `precedence`.
+    fn at(self, precedence: Precedence) -> String {
+        if self.precedence < precedence {
+            format!("({})", self.text)
+        } else {
+            self.text
+        }
+    }
+}
+
+impl fmt::Display for Ebnf {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.text)
+    }
+}
+
+/// Returns the minimum and maximum, if any, number of elements in `range`.
+fn bounds(range: &impl RangeBounds<usize>) -> (usize, Option<usize>) {
+    let min = match range.start_bound() {
+        Bound::Included(&n) => n,
+        Bound::Excluded(&n) => n + 1,
+        Bound::Unbounded => 0,
+    };
+
+    let max = match range.end_bound() {
+        Bound::Included(&n) => Some(n),
+        Bound::Excluded(&n) => Some(n.saturating_sub(1)),
+        Bound::Unbounded => None,
+    };
+
+    (min, max)
+}
+
+/// Writes sorted ASCII characters as the body of a class, collapsing runs into ranges.
+fn class_ranges(chars: &[char]) -> String {
+    let escape = |c: char| match c {
+        '\\' | ']' | '^' | '-' => format!("\\{c}"),
+        _ => c.escape_default().to_string(),
+    };
+
+    let mut text = String::new();
+    let mut i = 0;
+
+    while i < chars.len() {
+        let mut j = i;
+        while j + 1 < chars.len() && chars[j + 1] as u32 == chars[j] as u32 + 1 {
+            j += 1;
+        }
+
+        if j - i >= 2 {
+            text += &format!("{}-{}", escape(chars[i]), escape(chars[j]));
+        } else {
+            chars[i..=j].iter().for_each(|&c| text += &escape(c));
+        }
+        i = j + 1;
+    }
+
+    text
+}
+
+/// The named rules found while describing a parser, in order of appearance.
+#[derive(Default)]
+pub struct Rules {
+    rules: Vec<(String, Option<Ebnf>)>,
+    recursive: Vec<Recursion>,
+}
+
+/// A recursive parser found while describing a parser.
+struct Recursion {
+    key: usize,
+    name: String,
+    referenced: bool,
+}
+
+impl Rules {
+    /// Defines a rule named `name` with the expression produced by `define`, unless it is already
+    /// defined or being defined, which allows recursive rules.
+    ///
+    /// # Returns
+    /// A reference to the rule.
+    pub fn rule(&mut self, name: &str, define: impl FnOnce(&mut Rules) -> Ebnf) -> Ebnf {
+        if !self.rules.iter().any(|(rule, _)| rule == name) {
+            let i = self.rules.len();
+            self.rules.push((name.to_string(), None));
+            self.rules[i].1 = Some(define(self));
+        }
+
+        Ebnf::atom(name)
+    }
+
+    /// Returns a name starting with `prefix` that isn't used by any rule.
+    pub fn fresh_name(&self, prefix: &str) -> String {
+        (1..)
+            .map(|i| match i {
+                1 => prefix.to_string(),
+                _ => format!("{prefix}{i}"),
+            })
+            .find(|name| self.rules.iter().all(|(rule, _)| rule != name))
+            .unwrap_or_default()
+    }
+
+    /// Describes a recursive parser identified by `key`, with the expression produced by `define`.
+    ///
+    /// References to the parser inside its own definition are written by name. The name is the
+    /// label of the definition if it has one, or a fresh name otherwise.
+    pub(super) fn recursive(
+        &mut self,
+        key: usize,
+        define: impl FnOnce(&mut Rules) -> Ebnf,
+    ) -> Ebnf {
+        if let Some(recursion) = self.recursive.iter_mut().find(|r| r.key == key) {
+            recursion.referenced = true;
+            return Ebnf::atom(recursion.name.clone());
+        }
+
+        // Placeholder for the references until the name is known, which can't appear in literals.
+        let placeholder = format!("\u{1}{key}\u{1}");
+        self.recursive.push(Recursion {
+            key,
+            name: placeholder.clone(),
+            referenced: false,
+        });
+
+        let definition = define(self);
+        let i = self
+            .recursive
+            .iter()
+            .position(|r| r.key == key)
+            .unwrap_or_default();
+
+        if !self.recursive[i].referenced {
+            self.recursive.remove(i);
+            return definition;
+        }
+
+        let name = if definition.precedence == Precedence::Atom
+            && self.rules.iter().any(|(rule, _)| *rule == definition.text)
+        {
+            definition.text
+        } else {
+            let name = self.fresh_name("recursive");
+            self.rules.push((name.clone(), Some(definition)));
+            name
+        };
+
+        for (_, definition) in &mut self.rules {
+            if let Some(definition) = definition {
+                definition.text = definition.text.replace(&placeholder, &name);
+            }
+        }
+        self.recursive[i].name = name.clone();
+
+        Ebnf::atom(name)
+    }
+
+    /// Writes the expression of the parser followed by the rules, one per line.
+    pub(super) fn render(self, top: Ebnf) -> String {
+        let mut lines = Vec::new();
+
+        if self.rules.first().is_none_or(|(name, _)| *name != top.text) {
+            lines.push(top.text);
+        }
+
+        lines.extend(self.rules.into_iter().map(|(name, definition)| {
+            let definition = definition.map_or_else(String::new, |d| d.text);
+            format!("{name} = {definition} ;")
+        }));
+
+        lines.join("\n")
+    }
+}
+
+/////////////////////////////////////////////////
+//                    Label                    //
+/////////////////////////////////////////////////
+
+/// Parser that gives a name to another parser in its description.
+#[derive(Clone, Copy)]
+pub struct Label<P> {
+    pub(super) parser: P,
+    pub(super) name: &'static str,
+}
+
+impl<'a, I: Input + ?Sized, P: Parser<'a, I>> Parser<'a, I> for Label<P> {
+    type Output = P::Output;
+
+    fn parse(&self, input: &'a I) -> Option<(Self::Output, &'a I)> {
+        self.parser.parse(input)
+    }
+
+    fn describe_into(&self, rules: &mut Rules) -> Ebnf {
+        rules.rule(self.name, |rules| self.parser.describe_into(rules))
+    }
+}
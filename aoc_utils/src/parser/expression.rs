@@ -0,0 +1,282 @@
+//! Parsing of expressions with prefix, infix and postfix operators by precedence climbing.
+
+use super::{BoxedParser, Ebnf, Parser, ParserImpl, Rules};
+
+use std::rc::Rc;
+
+/// Associativity of an infix operator.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Assoc {
+    /// `a - b - c` is `(a - b) - c`.
+    Left,
+    /// `a ^ b ^ c` is `a ^ (b ^ c)`.
+    Right,
+}
+
+struct Unary<'a, T> {
+    op: BoxedParser<'a, ()>,
+    precedence: u32,
+    f: Rc<dyn Fn(T) -> T + 'a>,
+}
+
+struct Binary<'a, T> {
+    op: BoxedParser<'a, ()>,
+    precedence: u32,
+    assoc: Assoc,
+    f: Rc<dyn Fn(T, T) -> T + 'a>,
+}
+
+impl<T> Clone for Unary<'_, T> {
+    fn clone(&self) -> Self {
+        Unary {
+            op: self.op.clone(),
+            precedence: self.precedence,
+            f: Rc::clone(&self.f),
+        }
+    }
+}
+
+impl<T> Clone for Binary<'_, T> {
+    fn clone(&self) -> Self {
+        Binary {
+            op: self.op.clone(),
+            precedence: self.precedence,
+            assoc: self.assoc,
+            f: Rc::clone(&self.f),
+        }
+    }
+}
+
+//////////////////////////////////////////////////////
+//                    Expression                    //
+//////////////////////////////////////////////////////
+
+/// Parser of expressions made of atoms and operators with precedence and associativity.
+pub struct Expression<'a, T> {
+    atom: BoxedParser<'a, T>,
+    prefix: Vec<Unary<'a, T>>,
+    infix: Vec<Binary<'a, T>>,
+    postfix: Vec<Unary<'a, T>>,
+}
+
+impl<T> Clone for Expression<'_, T> {
+    fn clone(&self) -> Self {
+        Expression {
+            atom: self.atom.clone(),
+            prefix: self.prefix.clone(),
+            infix: self.infix.clone(),
+            postfix: self.postfix.clone(),
+        }
+    }
+}
+
+/// Creates a parser of expressions over `atom` without operators.
+/// Operators are declared with [`prefix`](ParserImpl::prefix), [`infix`](ParserImpl::infix)
+/// and [`postfix`](ParserImpl::postfix).
+///
+/// Operators with higher precedence bind tighter. The output can be either the evaluated value or
+/// an AST, depending on what the operator functions build.
+///
+/// # Arguments
+/// * `atom`: The parser for the operands.
+///
+/// # Returns
+/// A new `Parser` that produces values of type `T`.
+///
+/// # Examples
+/// ```
+/// use aoc_utils::parser::*;
+///
+/// let expr = recursive(|expr| {
+///     let atom = number().map(|n| n as i64).lexeme() | expr.between(symbol("("), symbol(")"));
+///
+///     expression(atom)
+///         .prefix(symbol("-"), 3, |x| -x)
+///         .infix(symbol("+"), 1, Assoc::Left, |a, b| a + b)
+///         .infix(symbol("-"), 1, Assoc::Left, |a, b| a - b)
+///         .infix(symbol("*"), 2, Assoc::Left, |a, b| a * b)
+///         .infix(symbol("^"), 4, Assoc::Right, |a, b| a.pow(b as u32))
+///         .postfix(symbol("!"), 5, |x| (1..=x).product())
+/// });
+///
+/// assert_eq!(expr.run_parser("1 + 2 * 3"), Some(7));
+/// assert_eq!(expr.run_parser("(1 + 2) * 3"), Some(9));
+/// assert_eq!(expr.run_parser("10 - 2 - 3"), Some(5));
+/// assert_eq!(expr.run_parser("2 ^ 3 ^ 2"), Some(512));
+/// assert_eq!(expr.run_parser("-3! + 1"), Some(-5));
+///
+/// // Puzzles with unusual rules only need different precedences.
+/// let weird = expression(number().lexeme())
+///     .infix(symbol("+"), 2, Assoc::Left, |a, b| a + b)
+///     .infix(symbol("*"), 1, Assoc::Left, |a, b| a * b);
+///
+/// assert_eq!(weird.run_parser("2 * 3 + 4"), Some(14));
+/// ```
+pub fn expression<'a, T, P>(atom: P) -> ParserImpl<Expression<'a, T>>
+where
+    P: Parser<'a, Output = T> + 'a,
+{
+    ParserImpl(Expression {
+        atom: atom.boxed(),
+        prefix: vec![],
+        infix: vec![],
+        postfix: vec![],
+    })
+}
+
+impl<'a, T> ParserImpl<Expression<'a, T>> {
+    /// Declares a prefix operator.
+    ///
+    /// # Arguments
+    /// * `op`: The parser for the operator.
+    /// * `precedence`: How tightly the operator binds its operand.
+    /// * `f`: A closure that applies the operator to its operand.
+    ///
+    /// # Returns
+    /// The same expression parser with the new operator.
+    pub fn prefix<Q, F>(mut self, op: Q, precedence: u32, f: F) -> Self
+    where
+        Q: Parser<'a> + 'a,
+        F: Fn(T) -> T + 'a,
+    {
+        self.0.prefix.push(Unary {
+            op: op.map(|_| ()).boxed(),
+            precedence,
+            f: Rc::new(f),
+        });
+        self
+    }
+
+    /// Declares an infix operator.
+    ///
+    /// # Arguments
+    /// * `op`: The parser for the operator.
+    /// * `precedence`: How tightly the operator binds its operands.
+    /// * `assoc`: How operators with the same precedence are grouped.
+    /// * `f`: A closure that applies the operator to its operands.
+    ///
+    /// # Returns
+    /// The same expression parser with the new operator.
+    pub fn infix<Q, F>(mut self, op: Q, precedence: u32, assoc: Assoc, f: F) -> Self
+    where
+        Q: Parser<'a> + 'a,
+        F: Fn(T, T) -> T + 'a,
+    {
+        self.0.infix.push(Binary {
+            op: op.map(|_| ()).boxed(),
+            precedence,
+            assoc,
+            f: Rc::new(f),
+        });
+        self
+    }
+
+    /// Declares a postfix operator.
+    ///
+    /// # Arguments
+    /// * `op`: The parser for the operator.
+    /// * `precedence`: How tightly the operator binds its operand.
+    /// * `f`: A closure that applies the operator to its operand.
+    ///
+    /// # Returns
+    /// The same expression parser with the new operator.
+    pub fn postfix<Q, F>(mut self, op: Q, precedence: u32, f: F) -> Self
+    where
+        Q: Parser<'a> + 'a,
+        F: Fn(T) -> T + 'a,
+    {
+        self.0.postfix.push(Unary {
+            op: op.map(|_| ()).boxed(),
+            precedence,
+            f: Rc::new(f),
+        });
+        self
+    }
+}
+
+impl<'a, T> Expression<'a, T> {
+    fn parse_operand(&self, input: &'a str) -> Option<(T, &'a str)> {
+        self.prefix
+            .iter()
+            .find_map(|unary| {
+                let (_, rem) = unary.op.parse(input)?;
+                let (operand, rem) = self.parse_min(rem, unary.precedence)?;
+                Some(((unary.f)(operand), rem))
+            })
+            .or_else(|| self.atom.parse(input))
+    }
+
+    fn parse_min(&self, input: &'a str, min: u32) -> Option<(T, &'a str)> {
+        let (mut lhs, mut input) = self.parse_operand(input)?;
+
+        'outer: loop {
+            for unary in self.postfix.iter().filter(|u| u.precedence >= min) {
+                if let Some((_, rem)) = unary.op.parse(input) {
+                    lhs = (unary.f)(lhs);
+                    input = rem;
+                    continue 'outer;
+                }
+            }
+
+            for binary in self.infix.iter().filter(|b| b.precedence >= min) {
+                let next_min = match binary.assoc {
+                    Assoc::Left => binary.precedence + 1,
+                    Assoc::Right => binary.precedence,
+                };
+
+                if let Some((_, rem)) = binary.op.parse(input)
+                    && let Some((rhs, rem)) = self.parse_min(rem, next_min)
+                {
+                    lhs = (binary.f)(lhs, rhs);
+                    input = rem;
+                    continue 'outer;
+                }
+            }
+
+            return Some((lhs, input));
+        }
+    }
+}
+
+impl<'a, T> Parser<'a> for Expression<'a, T> {
+    type Output = T;
+
+    fn parse(&self, input: &'a str) -> Option<(Self::Output, &'a str)> {
+        self.parse_min(input, 0)
+    }
+
+    fn describe_into(&self, rules: &mut Rules) -> Ebnf {
+        let ops = |ops: &[Unary<'a, T>], rules: &mut Rules| {
+            Ebnf::alternative(
+                ops.iter()
+                    .map(|u| u.op.describe_into(rules))
+                    .collect::<Vec<_>>(),
+            )
+        };
+
+        let mut operand = vec![];
+        if !self.prefix.is_empty() {
+            operand.push(ops(&self.prefix, rules).postfix("*"));
+        }
+        operand.push(self.atom.describe_into(rules));
+        if !self.postfix.is_empty() {
+            operand.push(ops(&self.postfix, rules).postfix("*"));
+        }
+        let operand = Ebnf::sequence(operand);
+
+        if self.infix.is_empty() {
+            return operand;
+        }
+
+        let infix = Ebnf::alternative(
+            self.infix
+                .iter()
+                .map(|b| b.op.describe_into(rules))
+                .collect::<Vec<_>>(),
+        );
+        Ebnf::sequence([
+            operand.clone(),
+            Ebnf::sequence([infix, operand]).postfix("*"),
+        ])
+    }
+}
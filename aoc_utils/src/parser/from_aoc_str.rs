@@ -0,0 +1,344 @@
+//! Parsing of values whose parser is inferred from their type.
+//!
+//! Containers pick the separator between their elements by how deeply they are nested:
+//! * Containers of scalars, like `(usize, usize)` or `Vec<i32>`, expect the elements in the same line,
+//!   separated by one of `,;:|` with optional spaces around, or by spaces.
+//! * Containers of `char`, like `Vec<char>`, have no separator at all, so they parse a row of a grid
+//!   keeping every character, including spaces and punctuation.
+//! * Containers of those, like `Vec<(usize, usize)>`, expect an element per line.
+//! * Deeper containers expect the elements separated by blank lines.
+//!
+//! [`Separated`] overrides the separator of a list, and [`SeparatedTuple`] the one of a tuple, which
+//! is how ranges like `3-5` are parsed.
+
+use super::{Parser, ParserImpl, char, newline, one_of, p, satisfies, spaces, take_while1};
+
+/// Types that can be parsed from an input without an explicit parser.
+pub trait FromAocStr: Sized {
+    /// How deeply nested the type is: 0 for scalars, one more than its elements for containers.
+    const LEVEL: usize;
+
+    /// Whether the type is a cell of a grid, so containers of it have no separator between elements.
+    const CELL: bool = false;
+
+    /// Parses a value from the start of the input.
+    ///
+    /// # Arguments
+    /// * `input`: The string slice to parse.
+    ///
+    /// # Returns
+    /// An `Option` with the value and the remaining string slice, or `None` on failure.
+    fn parse_aoc(input: &str) -> Option<(Self, &str)>;
+}
+
+/// A parser that parses values of type `T` through [`FromAocStr`].
+pub type AocParser<T> = ParserImpl<for<'a> fn(&'a str) -> Option<(T, &'a str)>>;
+
+/// Creates a parser for values of type `T`, inferred from the type.
+///
+/// # Returns
+/// A new `Parser` that produces values of type `T`.
+///
+/// # Examples
+/// ```
+/// use aoc_utils::parser::*;
+///
+/// assert_eq!(aoc::<(usize, usize)>().run_parser("1,3"), Some((1, 3)));
+/// assert_eq!(aoc::<Vec<i32>>().run_parser("3, -1, 4"), Some(vec![3, -1, 4]));
+/// assert_eq!(aoc::<Vec<i32>>().run_parser("3 -1"), Some(vec![3, -1]));
+/// assert_eq!((aoc::<Vec<i32>>() << eof()).run_parser("3-1"), None);
+/// assert_eq!(aoc::<(String, char)>().run_parser("abc: d"), Some(("abc".to_string(), 'd')));
+/// assert_eq!(
+///     aoc::<Vec<[u8; 2]>>().run_parser("1 2\n3 4"),
+///     Some(vec![[1, 2], [3, 4]])
+/// );
+/// assert_eq!(
+///     aoc::<Vec<Vec<Vec<char>>>>().run_parser("#.\n.#\n\n##"),
+///     Some(vec![vec![vec!['#', '.'], vec!['.', '#']], vec![vec!['#', '#']]])
+/// );
+///
+/// // Rows of a grid keep every cell, even the ones that look like separators.
+/// assert_eq!(
+///     aoc::<Vec<Vec<char>>>().run_parser("|-.\n. |"),
+///     Some(vec![vec!['|', '-', '.'], vec!['.', ' ', '|']])
+/// );
+/// assert_eq!(aoc::<Vec<char>>().run_parser("a b,c"), Some(vec!['a', ' ', 'b', ',', 'c']));
+/// ```
+pub fn aoc<T: FromAocStr>() -> AocParser<T> {
+    p(T::parse_aoc)
+}
+
+/// Parses the default separator between the elements of a container of the given level.
+///
+/// # Arguments
+/// * `level`: The [`LEVEL`](FromAocStr::LEVEL) of the container.
+/// * `input`: The string slice to parse.
+///
+/// # Returns
+/// An `Option` with the remaining string slice, or `None` if there is no separator.
+///
+/// # Examples
+/// ```
+/// use aoc_utils::parser::*;
+///
+/// assert_eq!(aoc_separator(1, ", 2"), Some(((), "2")));
+/// assert_eq!(aoc_separator(1, "-2"), None);
+/// assert_eq!(aoc_separator(2, "\n2"), Some(((), "2")));
+/// assert_eq!(aoc_separator(2, ", 2"), None);
+/// ```
+pub fn aoc_separator(level: usize, input: &str) -> Option<((), &str)> {
+    match level {
+        0 | 1 => (spaces() >> one_of(",;:|") << spaces())
+            .value(())
+            .or(take_while1(|c| c == ' ' || c == '\t').value(()))
+            .parse(input),
+        2 => newline().value(()).parse(input),
+        _ => newline().repeat(2..).value(()).parse(input),
+    }
+}
+
+/// Parses the separator between the elements of a list of `T`, which is empty for cells of a grid.
+fn list_separator<T: FromAocStr>(level: usize, input: &str) -> Option<((), &str)> {
+    match T::CELL {
+        true => Some(((), input)),
+        false => aoc_separator(level, input),
+    }
+}
+
+/// Parses `count` elements, or as many as possible if it is `None`, with `sep` between them.
+fn elements<'a, T: FromAocStr>(
+    mut input: &'a str,
+    sep: impl Fn(&'a str) -> Option<((), &'a str)>,
+    count: Option<usize>,
+) -> Option<(Vec<T>, &'a str)> {
+    let mut items = vec![];
+
+    while count.is_none_or(|n| items.len() < n) {
+        let start = match items.is_empty() {
+            true => input,
+            false => match sep(input) {
+                Some((_, remaining)) => remaining,
+                None => break,
+            },
+        };
+
+        match T::parse_aoc(start) {
+            Some((item, remaining)) if remaining.len() < start.len() => {
+                items.push(item);
+                input = remaining;
+            }
+            _ => break,
+        }
+    }
+
+    count
+        .is_none_or(|n| items.len() == n)
+        .then_some((items, input))
+}
+
+/// Returns the level of a container with elements of the given levels.
+const fn container_level(levels: &[usize]) -> usize {
+    let mut max = 0;
+    let mut i = 0;
+    while i < levels.len() {
+        if levels[i] > max {
+            max = levels[i];
+        }
+        i += 1;
+    }
+    max + 1
+}
+
+macro_rules! unsigned {
+    ($($T:ty),+) => {$(
+        impl FromAocStr for $T {
+            const LEVEL: usize = 0;
+
+            fn parse_aoc(input: &str) -> Option<(Self, &str)> {
+                let end = input.find(|c: char| !c.is_ascii_digit()).unwrap_or(input.len());
+                input[..end].parse().ok().map(|n| (n, &input[end..]))
+            }
+        }
+    )+};
+}
+
+macro_rules! signed {
+    ($($T:ty),+) => {$(
+        impl FromAocStr for $T {
+            const LEVEL: usize = 0;
+
+            fn parse_aoc(input: &str) -> Option<(Self, &str)> {
+                let sign = usize::from(input.starts_with('-'));
+                let end = input[sign..]
+                    .find(|c: char| !c.is_ascii_digit())
+                    .map_or(input.len(), |i| i + sign);
+                input[..end].parse().ok().map(|n| (n, &input[end..]))
+            }
+        }
+    )+};
+}
+
+unsigned!(u8, u16, u32, u64, u128, usize);
+signed!(i8, i16, i32, i64, i128, isize);
+
+impl FromAocStr for char {
+    const LEVEL: usize = 0;
+    const CELL: bool = true;
+
+    /// Parses any character other than a line break.
+    fn parse_aoc(input: &str) -> Option<(Self, &str)> {
+        satisfies(|c| c != '\n' && c != '\r').parse(input)
+    }
+}
+
+impl FromAocStr for String {
+    const LEVEL: usize = 0;
+
+    /// Parses a word, a run of characters other than whitespace and separators.
+    fn parse_aoc(input: &str) -> Option<(Self, &str)> {
+        let end = input
+            .find(|c: char| c.is_whitespace() || ",;:|-".contains(c))
+            .unwrap_or(input.len());
+        (end > 0).then(|| (input[..end].to_string(), &input[end..]))
+    }
+}
+
+impl<T: FromAocStr> FromAocStr for Vec<T> {
+    const LEVEL: usize = T::LEVEL + 1;
+
+    fn parse_aoc(input: &str) -> Option<(Self, &str)> {
+        elements(input, |s| list_separator::<T>(Self::LEVEL, s), None)
+            .filter(|(items, _)| !items.is_empty())
+    }
+}
+
+impl<T: FromAocStr, const N: usize> FromAocStr for [T; N] {
+    const LEVEL: usize = T::LEVEL + 1;
+
+    fn parse_aoc(input: &str) -> Option<(Self, &str)> {
+        let (items, remaining) = elements(input, |s| list_separator::<T>(Self::LEVEL, s), Some(N))?;
+        items.try_into().ok().map(|items| (items, remaining))
+    }
+}
+
+/// A list whose elements are separated by `SEP` instead of the default separator.
+///
+/// # Examples
+/// ```
+/// use aoc_utils::parser::*;
+///
+/// let dimensions = aoc::<Separated<u32, 'x'>>().map(|Separated(v)| v);
+///
+/// assert_eq!(dimensions.run_parser("2x3x4"), Some(vec![2, 3, 4]));
+/// ```
+#[derive(Clone, PartialEq, Eq, Hash, Debug, Default)]
+pub struct Separated<T, const SEP: char>(pub Vec<T>);
+
+impl<T: FromAocStr, const SEP: char> FromAocStr for Separated<T, SEP> {
+    const LEVEL: usize = T::LEVEL + 1;
+
+    fn parse_aoc(input: &str) -> Option<(Self, &str)> {
+        elements(input, |s| char(SEP).value(()).parse(s), None)
+            .filter(|(items, _)| !items.is_empty())
+            .map(|(items, remaining)| (Separated(items), remaining))
+    }
+}
+
+/// Tuples whose elements are parsed through [`FromAocStr`], with any separator between them.
+pub trait AocTuple: Sized {
+    /// The deepest `LEVEL` of the elements.
+    const ELEMENT_LEVEL: usize;
+
+    /// Parses the elements in order with `sep` between them.
+    ///
+    /// # Arguments
+    /// * `input`: The string slice to parse.
+    /// * `sep`: The parser of the separator.
+    ///
+    /// # Returns
+    /// An `Option` with the tuple and the remaining string slice, or `None` on failure.
+    fn parse_elements<'a>(
+        input: &'a str,
+        sep: impl Fn(&'a str) -> Option<((), &'a str)>,
+    ) -> Option<(Self, &'a str)>;
+}
+
+/// A tuple whose elements are separated by `SEP` instead of the default separator.
+///
+/// The separator is explicit, so it counts as deep as its elements when picking the separators
+/// around it.
+///
+/// # Examples
+/// ```
+/// use aoc_utils::parser::*;
+///
+/// let range = aoc::<SeparatedTuple<(usize, usize), '-'>>().map(|SeparatedTuple(r)| r);
+///
+/// assert_eq!(range.run_parser("3-5"), Some((3, 5)));
+/// assert_eq!(aoc::<(usize, usize)>().run_parser("3-5"), None);
+///
+/// // A line of a list of pairs of ranges.
+/// type Range = SeparatedTuple<(u32, u32), '-'>;
+///
+/// assert_eq!(
+///     aoc::<Vec<(Range, Range)>>().run_parser("2-4,6-8\n2-3,4-5"),
+///     Some(vec![
+///         (SeparatedTuple((2, 4)), SeparatedTuple((6, 8))),
+///         (SeparatedTuple((2, 3)), SeparatedTuple((4, 5))),
+///     ])
+/// );
+/// ```
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug, Default)]
+pub struct SeparatedTuple<T, const SEP: char>(pub T);
+
+impl<T: AocTuple, const SEP: char> FromAocStr for SeparatedTuple<T, SEP> {
+    const LEVEL: usize = T::ELEMENT_LEVEL;
+
+    fn parse_aoc(input: &str) -> Option<(Self, &str)> {
+        T::parse_elements(input, |s| char(SEP).value(()).parse(s))
+            .map(|(tuple, remaining)| (SeparatedTuple(tuple), remaining))
+    }
+}
+
+macro_rules! tuples {
+    ($(($T1:ident, $($T:ident),+);)+) => {$(
+        impl<$T1: FromAocStr, $($T: FromAocStr),+> AocTuple for ($T1, $($T),+) {
+            const ELEMENT_LEVEL: usize = container_level(&[$T1::LEVEL, $($T::LEVEL),+]) - 1;
+
+            #[allow(non_snake_case)]
+            fn parse_elements<'a>(
+                input: &'a str,
+                sep: impl Fn(&'a str) -> Option<((), &'a str)>,
+            ) -> Option<(Self, &'a str)> {
+                let ($T1, input) = $T1::parse_aoc(input)?;
+                $(
+                    let (_, input) = sep(input)?;
+                    let ($T, input) = $T::parse_aoc(input)?;
+                )+
+                Some((($T1, $($T),+), input))
+            }
+        }
+
+        impl<$T1: FromAocStr, $($T: FromAocStr),+> FromAocStr for ($T1, $($T),+) {
+            const LEVEL: usize = Self::ELEMENT_LEVEL + 1;
+
+            fn parse_aoc(input: &str) -> Option<(Self, &str)> {
+                Self::parse_elements(input, |s| aoc_separator(Self::LEVEL, s))
+            }
+        }
+    )+};
+}
+
+tuples! {
+    (A, B);
+    (A, B, C);
+    (A, B, C, D);
+    (A, B, C, D, E);
+    (A, B, C, D, E, F);
+    (A, B, C, D, E, F, G);
+    (A, B, C, D, E, F, G, H);
+    (A, B, C, D, E, F, G, H, I);
+    (A, B, C, D, E, F, G, H, I, J);
+    (A, B, C, D, E, F, G, H, I, J, K);
+    (A, B, C, D, E, F, G, H, I, J, K, L);
+}
@@ -0,0 +1,82 @@
+//! Types of input parsers can run over.
+//!
+//! Parsers run over `&str` by default. Byte grids and binary data can be parsed as `&[u8]` with the
+//! parsers in [`bytes`](super::bytes), and pre-lexed inputs as `&[Token]`, writing the primitives
+//! over tokens as functions.
+
+use std::fmt;
+
+/// An input parsers can consume from the front, like `str` or a slice.
+///
+/// Positions in the input are counted in bytes for `str` and in elements for slices.
+pub trait Input {
+    /// Returns the length of the input.
+    fn len(&self) -> usize;
+
+    /// Returns whether the input is empty.
+    fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Divides the input in two at a position.
+    ///
+    /// # Arguments
+    /// * `mid`: The position to split at, which must be at most the length of the input.
+    ///
+    /// # Returns
+    /// A tuple with the input before `mid` and the input from `mid` on.
+    fn split_at(&self, mid: usize) -> (&Self, &Self);
+
+    /// Returns the length of the first unit of the input, a char or an element,
+    /// or `None` if the input is empty.
+    fn first_len(&self) -> Option<usize>;
+
+    /// Renders the input for traces.
+    fn render(&self) -> String;
+}
+
+impl Input for str {
+    fn len(&self) -> usize {
+        str::len(self)
+    }
+
+    fn split_at(&self, mid: usize) -> (&Self, &Self) {
+        str::split_at(self, mid)
+    }
+
+    fn first_len(&self) -> Option<usize> {
+        self.chars().next().map(char::len_utf8)
+    }
+
+    fn render(&self) -> String {
+        self.to_string()
+    }
+}
+
+impl<T: fmt::Debug> Input for [T] {
+    fn len(&self) -> usize {
+        <[T]>::len(self)
+    }
+
+    fn split_at(&self, mid: usize) -> (&Self, &Self) {
+        <[T]>::split_at(self, mid)
+    }
+
+    fn first_len(&self) -> Option<usize> {
+        (!self.is_empty()).then_some(1)
+    }
+
+    fn render(&self) -> String {
+        format!("{self:?}")
+    }
+}
+
+/// Returns the length of the part of `input` consumed before `remaining`.
+pub(crate) fn consumed<I: Input + ?Sized>(input: &I, remaining: &I) -> usize {
+    input.len() - remaining.len()
+}
+
+/// Returns the address of the first unit of `input`.
+pub(crate) fn address<I: ?Sized>(input: &I) -> usize {
+    input as *const I as *const u8 as usize
+}
@@ -0,0 +1,48 @@
+//! Iterators that run parsers repeatedly over an input.
+
+use super::{Input, Parser, context::Context};
+
+use std::ops::Range;
+
+///////////////////////////////////////////////////
+//                    Matches                    //
+///////////////////////////////////////////////////
+
+/// Iterator over every non-overlapping match of a parser in an input.
+///
+/// Created by [`Parser::iter_matches`].
+pub struct Matches<'p, 'a, P, I: ?Sized = str> {
+    pub(super) parser: &'p P,
+    pub(super) input: &'a I,
+    pub(super) offset: usize,
+    pub(super) context: Context,
+}
+
+impl<P, I: ?Sized> Clone for Matches<'_, '_, P, I> {
+    fn clone(&self) -> Self {
+        Matches { ..*self }
+    }
+}
+
+impl<'a, I: Input + ?Sized, P: Parser<'a, I>> Iterator for Matches<'_, 'a, P, I> {
+    type Item = (P::Output, Range<usize>);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while self.offset <= self.input.len() {
+            let rest = self.input.split_at(self.offset).1;
+            let start = self.offset;
+
+            match self.context.enter(|| self.parser.parse(rest)) {
+                Some((output, remaining)) if remaining.len() < rest.len() => {
+                    self.offset = self.input.len() - remaining.len();
+                    return Some((output, start..self.offset));
+                }
+                _ => {
+                    self.offset += rest.first_len().unwrap_or(1);
+                }
+            }
+        }
+
+        None
+    }
+}
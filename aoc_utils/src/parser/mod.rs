@@ -1,39 +1,193 @@
-//! This module, inspired in Haskell's Parsec, defines simple parser combinators of string slices.
+//! This module, inspired in Haskell's Parsec, defines simple parser combinators of string slices
+//! and other [`Input`]s.
 
+pub mod bytes;
 mod combinator;
+mod context;
+mod describe;
+mod expression;
+mod from_aoc_str;
+mod input;
+mod iter;
+mod nondet;
 mod operators;
+mod permutation;
 mod primitives;
+mod regex;
+mod sections;
+mod span;
+mod state;
+mod trace;
 
 pub use combinator::*;
+pub use describe::{Ebnf, Label, Rules};
+pub use expression::*;
+pub use from_aoc_str::*;
+pub use input::Input;
+pub use iter::*;
+pub use nondet::*;
+pub use permutation::*;
 pub use primitives::*;
+pub use regex::*;
+pub use sections::*;
+pub use span::*;
+pub use state::{MapWithState, State, StateSlot, state};
+pub use trace::{Trace, Traced, debug, set_debug};
 
-use std::ops::RangeBounds;
+pub(crate) use context::within;
 
-/// A parser takes string slices as input and produces values of type `Output` along with
-/// the remaining unconsumed string slice.
-pub trait Parser: Sized {
+use context::Context;
+
+use std::{
+    ops::{RangeBounds, RangeInclusive},
+    rc::Rc,
+};
+
+/// A parser takes an input, a string slice by default, and produces values of type `Output` along
+/// with the remaining unconsumed input.
+///
+/// The input can also be any other [`Input`], like `[u8]` or a slice of tokens, by setting `I`.
+/// The combinator methods work over any input, while most primitives and the operators
+/// (`|`, `+`, `>>`, `<<`, `!` and `*`) are specific to `str`. Over other inputs, the operators
+/// are written with their methods, like [`or`](Parser::or) or [`then_zip`](Parser::then_zip).
+///
+/// The trait is object safe, so parsers can also be used as `dyn Parser`.
+pub trait Parser<'a, I: Input + ?Sized = str> {
     type Output;
 
-    /// Parses the input string slice and returns an `Option` containing
-    /// a tuple of the parsed value and the remaining unconsumed string slice.
+    /// Parses the input and returns an `Option` containing
+    /// a tuple of the parsed value and the remaining unconsumed input.
+    ///
+    /// # Arguments
+    /// * `input`: The input to parse.
+    ///
+    /// # Returns
+    /// An `Option<(Self::Output, &I)>` containing the parsed value and
+    /// the remaining input on success, or `None` on failure.
+    fn parse(&self, input: &'a I) -> Option<(Self::Output, &'a I)>;
+
+    /// Describes the parser as an EBNF-like expression, adding the labeled parsers it uses to `rules`.
+    ///
+    /// Every parser in this module implements it. The default implementation describes the parser
+    /// by the name of its type, as in `<Name>`.
     ///
     /// # Arguments
-    /// * `input`: The string slice to parse.
+    /// * `rules`: The rules found so far.
+    ///
+    /// # Returns
+    /// The [`Ebnf`] expression of the parser.
+    fn describe_into(&self, rules: &mut Rules) -> Ebnf {
+        let _ = rules;
+        Ebnf::special(&trace::type_label::<Self>())
+    }
+
+    /// Renders the grammar accepted by the parser as EBNF-like text.
+    ///
+    /// The first line is the expression of the parser, followed by a line with the definition of
+    /// each labeled parser it uses. If the parser is labeled itself, only the rules are written.
+    ///
+    /// The notation is:
+    /// * `"abc"`: a literal string.
+    /// * `[a-z_]`, `[^,]`: a character class, computed from the ASCII characters it accepts.
+    /// * `a b`, `a | b`: sequence and ordered choice.
+    /// * `a?`, `a*`, `a+`, `a{n}`, `a{n,m}`: repetition.
+    /// * `&a`, `!a`: look ahead, succeeding if `a` succeeds or fails without consuming input.
+    /// * `<name>`: anything the notation can't express, like arbitrary closures.
     ///
     /// # Returns
-    /// An `Option<(Self::Output, &str)>` containing the parsed value and
-    /// the remaining string slice on success, or `None` on failure.
-    fn parse<'a>(&self, input: &'a str) -> Option<(Self::Output, &'a str)>;
+    /// A `String` with the grammar.
+    ///
+    /// # Examples
+    /// ```
+    /// use aoc_utils::parser::*;
+    ///
+    /// let int = (one_of("+-").optional() + number()).label("int");
+    /// let range = int + prefix("-") + int;
+    ///
+    /// assert_eq!(range.describe(), "int \"-\" int\nint = [+\\-]? [0-9]+ ;");
+    /// assert_eq!(
+    ///     (symbol("(") >> take_while(|c| c != ')') << symbol(")")).describe(),
+    ///     "\"(\" [\\t ]* [^)]* \")\" [\\t ]*"
+    /// );
+    /// ```
+    fn describe(&self) -> String {
+        let mut rules = Rules::default();
+        let top = self.describe_into(&mut rules);
+        rules.render(top)
+    }
 
     /// Runs the parser on the given input and returns only the parsed value, if successful.
     ///
     /// # Arguments
-    /// * `input`: The string slice to parse.
+    /// * `input`: The input to parse.
+    ///
+    /// # Returns
+    /// An `Option<Self::Output>` containing the parsed value on success, or `None` on failure.
+    ///
+    /// If the global debug switch is on ([`set_debug`]), the trace of the run, as recorded by
+    /// [`run_traced`](Parser::run_traced), is printed to stderr.
+    ///
+    /// Positions, like the ones of [`with_span`](Parser::with_span), are relative to `input`,
+    /// unless it is part of the input of a run in progress, whose positions are kept.
+    fn run_parser(&self, input: &'a I) -> Option<Self::Output> {
+        if trace::debug() {
+            let (output, trace) = self.run_traced(input);
+            eprint!("{trace}");
+            return output;
+        }
+
+        Context::for_run(input).enter(|| self.parse(input).map(|(output, _)| output))
+    }
+
+    /// Runs the parser on the given input like [`run_parser`](Parser::run_parser), recording the
+    /// attempts of the parsers wrapped in [`trace`](Parser::trace).
+    ///
+    /// # Arguments
+    /// * `input`: The input to parse.
+    ///
+    /// # Returns
+    /// A tuple with the parsed value, if any, and the [`Trace`] of the run, whose root is labeled `run`.
+    ///
+    /// # Examples
+    /// ```
+    /// use aoc_utils::parser::*;
+    ///
+    /// let sign = (prefix("+") | prefix("-")).trace("sign");
+    /// let int = (sign.optional() + number().trace("digits")).trace("int");
+    /// let (output, trace) = int.run_traced("12");
+    ///
+    /// assert_eq!(output, Some((None, 12)));
+    /// assert_eq!(
+    ///     trace.to_string(),
+    ///     "run @0 ok \"12\"\n  int @0 ok \"12\"\n    sign @0 failed\n    digits @0 ok \"12\"\n"
+    /// );
+    /// ```
+    fn run_traced(&self, input: &'a I) -> (Option<Self::Output>, Trace) {
+        let (result, trace) =
+            Context::for_run(input).enter(|| trace::collect("run", input, || self.parse(input)));
+        (result.map(|(output, _)| output), trace)
+    }
+
+    /// Runs the parser on the given input like [`run_parser`](Parser::run_parser), lending `state`
+    /// to `slot` so [`state`] and [`map_with_state`](Parser::map_with_state) can access it.
+    ///
+    /// # Arguments
+    /// * `slot`: The slot the parsers access the state through.
+    /// * `state`: The state, which holds the changes made by the parser once it finishes.
+    /// * `input`: The input to parse.
     ///
     /// # Returns
     /// An `Option<Self::Output>` containing the parsed value on success, or `None` on failure.
-    fn run_parser(&self, input: &str) -> Option<Self::Output> {
-        self.parse(input).map(|(output, _)| output)
+    fn parse_with_state<S>(
+        &self,
+        slot: &StateSlot<S>,
+        state: &mut S,
+        input: &'a I,
+    ) -> Option<Self::Output>
+    where
+        Self: Sized,
+    {
+        slot.enter(state, || self.run_parser(input))
     }
 
     /// Parses a range of occurrences of `self` and collects the results into a `Vec<Self::Output>`.
@@ -63,7 +217,10 @@ pub trait Parser: Sized {
     /// assert_eq!(digits.run_parser("123abc"), Some(vec!['1', '2']));
     /// assert_eq!(digits.run_parser("abc"), Some(vec![]));
     /// ```
-    fn repeat<R: RangeBounds<usize>>(self, range: R) -> ParserImpl<Repeat<Self, R>> {
+    fn repeat<R: RangeBounds<usize>>(self, range: R) -> ParserImpl<Repeat<Self, R>>
+    where
+        Self: Sized,
+    {
         ParserImpl(Repeat {
             parser: self,
             range,
@@ -98,11 +255,14 @@ pub trait Parser: Sized {
     /// assert_eq!(digits.run_parser("1,2,3abc"), Some(vec!['1', '2']));
     /// assert_eq!(digits.run_parser("abc"), Some(vec![]));
     /// ```
-    fn sep_by<Q: Parser, R: RangeBounds<usize>>(
+    fn sep_by<Q: Parser<'a, I>, R: RangeBounds<usize>>(
         self,
         rhs: Q,
         range: R,
-    ) -> ParserImpl<SepBy<Self, Q, R>> {
+    ) -> ParserImpl<SepBy<Self, Q, R>>
+    where
+        Self: Sized,
+    {
         ParserImpl(SepBy {
             parser: self,
             rhs,
@@ -127,10 +287,66 @@ pub trait Parser: Sized {
     /// assert_eq!(is_uppercase.run_parser("Hello!"), Some(true));
     /// assert_eq!(is_uppercase.run_parser("hello!"), Some(false));
     /// ```
-    fn map<U, F: Fn(Self::Output) -> U>(self, f: F) -> ParserImpl<Map<Self, F>> {
+    fn map<U, F: Fn(Self::Output) -> U>(self, f: F) -> ParserImpl<Map<Self, F>>
+    where
+        Self: Sized,
+    {
         ParserImpl(Map { parser: self, f })
     }
 
+    /// Transforms the output of the parser using a function that may fail, failing the parse if it does.
+    ///
+    /// # Arguments
+    /// * `f`: A closure that takes the parsed value `Self::Output` and returns an `Option` or a `Result`.
+    ///
+    /// # Returns
+    /// A new `Parser` that produces the successful values of `f`.
+    ///
+    /// # Examples
+    /// ```
+    /// use aoc_utils::parser::*;
+    ///
+    /// let digit_value = any_char().try_map(|c| c.to_digit(10));
+    ///
+    /// assert_eq!(digit_value.run_parser("7"), Some(7));
+    /// assert_eq!(digit_value.run_parser("x"), None);
+    ///
+    /// let byte = take_while1(|c| c.is_ascii_digit()).try_map(str::parse::<u8>);
+    ///
+    /// assert_eq!(byte.run_parser("255"), Some(255));
+    /// assert_eq!(byte.run_parser("256"), None);
+    /// ```
+    fn try_map<U: Fallible, F: Fn(Self::Output) -> U>(self, f: F) -> ParserImpl<TryMap<Self, F>>
+    where
+        Self: Sized,
+    {
+        ParserImpl(TryMap { parser: self, f })
+    }
+
+    /// Fails the parse if the output of the parser doesn't satisfy `pred`.
+    ///
+    /// # Arguments
+    /// * `pred`: A closure that takes a reference to the parsed value and returns whether it is valid.
+    ///
+    /// # Returns
+    /// A new `Parser` that produces the same values as `self` when they are valid.
+    ///
+    /// # Examples
+    /// ```
+    /// use aoc_utils::parser::*;
+    ///
+    /// let birth_year = number().verify(|year| (1920..=2002).contains(year));
+    ///
+    /// assert_eq!(birth_year.run_parser("1980"), Some(1980));
+    /// assert_eq!(birth_year.run_parser("2003"), None);
+    /// ```
+    fn verify<F: Fn(&Self::Output) -> bool>(self, pred: F) -> ParserImpl<Verify<Self, F>>
+    where
+        Self: Sized,
+    {
+        ParserImpl(Verify { parser: self, pred })
+    }
+
     /// Combines two parsers, trying the first one and running the second if the first fails.
     ///
     /// This can also be used via the `|` operator.
@@ -149,7 +365,10 @@ pub trait Parser: Sized {
     /// assert_eq!(digit_or_letter.run_parser("1!"), Some('1'));
     /// assert_eq!(digit_or_letter.run_parser("A!"), Some('A'));
     /// ```
-    fn or<Q: Parser<Output = Self::Output>>(self, rhs: Q) -> ParserImpl<Or<Self, Q>> {
+    fn or<Q: Parser<'a, I, Output = Self::Output>>(self, rhs: Q) -> ParserImpl<Or<Self, Q>>
+    where
+        Self: Sized,
+    {
         ParserImpl(Or { lhs: self, rhs })
     }
 
@@ -171,7 +390,10 @@ pub trait Parser: Sized {
     /// let ranking = prefix("#").ignored_then(digit);
     /// assert_eq!(ranking.run_parser("#1"), Some('1'));
     /// ```
-    fn ignored_then<Q: Parser>(self, rhs: Q) -> ParserImpl<IgnoredThen<Self, Q>> {
+    fn ignored_then<Q: Parser<'a, I>>(self, rhs: Q) -> ParserImpl<IgnoredThen<Self, Q>>
+    where
+        Self: Sized,
+    {
         ParserImpl(IgnoredThen { parser: self, rhs })
     }
 
@@ -192,7 +414,10 @@ pub trait Parser: Sized {
     /// let single_digit = satisfies(|c| c.is_digit(10)).then_ignore(eof());
     /// assert_eq!(single_digit.run_parser("5"), Some('5'));
     /// ```
-    fn then_ignore<Q: Parser>(self, rhs: Q) -> ParserImpl<ThenIgnore<Self, Q>> {
+    fn then_ignore<Q: Parser<'a, I>>(self, rhs: Q) -> ParserImpl<ThenIgnore<Self, Q>>
+    where
+        Self: Sized,
+    {
         ParserImpl(ThenIgnore { parser: self, rhs })
     }
 
@@ -214,7 +439,10 @@ pub trait Parser: Sized {
     /// let tuple = digit.then_zip(digit);
     /// assert_eq!(tuple.run_parser("12"), Some(('1', '2')));
     /// ```
-    fn then_zip<Q: Parser>(self, rhs: Q) -> ParserImpl<ThenZip<Self, Q>> {
+    fn then_zip<Q: Parser<'a, I>>(self, rhs: Q) -> ParserImpl<ThenZip<Self, Q>>
+    where
+        Self: Sized,
+    {
         ParserImpl(ThenZip { parser: self, rhs })
     }
 
@@ -236,11 +464,14 @@ pub trait Parser: Sized {
     ///
     /// assert_eq!(tuple.run_parser("12"), Some(('1', '2')));
     /// ```
-    fn then_zip_with<Q: Parser, V, F: Fn(Self::Output, Q::Output) -> V>(
+    fn then_zip_with<Q: Parser<'a, I>, V, F: Fn(Self::Output, Q::Output) -> V>(
         self,
         rhs: Q,
         f: F,
-    ) -> ParserImpl<ThenZipWith<Self, Q, F>> {
+    ) -> ParserImpl<ThenZipWith<Self, Q, F>>
+    where
+        Self: Sized,
+    {
         ParserImpl(ThenZipWith {
             parser: self,
             rhs,
@@ -264,10 +495,79 @@ pub trait Parser: Sized {
     ///
     /// assert_eq!(tuple.run_parser("12"), Some(('1', '2')));
     /// ```
-    fn and_then<Q: Parser, F: Fn(Self::Output) -> Q>(self, f: F) -> ParserImpl<AndThen<Self, F>> {
+    fn and_then<Q: Parser<'a, I>, F: Fn(Self::Output) -> Q>(
+        self,
+        f: F,
+    ) -> ParserImpl<AndThen<Self, F>>
+    where
+        Self: Sized,
+    {
         ParserImpl(AndThen { parser: self, f })
     }
 
+    /// Transforms the output of the parser using a function that also has access to the user state.
+    ///
+    /// # Arguments
+    /// * `slot`: The slot of the state.
+    /// * `f`: A closure that takes a mutable reference to the state and the parsed value `Self::Output`,
+    ///   and returns a new value `U`.
+    ///
+    /// # Returns
+    /// A new `Parser` that produces values of type `U`.
+    ///
+    /// # Panics
+    /// When parsing, if the parser isn't run with [`parse_with_state`](Parser::parse_with_state)
+    /// and the same slot.
+    ///
+    /// # Examples
+    /// ```
+    /// use aoc_utils::parser::*;
+    ///
+    /// use std::collections::HashMap;
+    ///
+    /// // The state can borrow from the input.
+    /// let ids: StateSlot<HashMap<&str, usize>> = StateSlot::new();
+    /// let device = take_while1(char::is_lowercase).map_with_state(&ids, |ids, name| {
+    ///     let next = ids.len();
+    ///     *ids.entry(name).or_insert(next)
+    /// });
+    /// let line = (device.clone() << prefix(":")) + (prefix(" ") >> device).repeat(1..);
+    /// let mut table = HashMap::new();
+    ///
+    /// assert_eq!(
+    ///     line.sep_by(newline(), ..).parse_with_state(&ids, &mut table, "aaa: you hhh\nyou: bbb aaa"),
+    ///     Some(vec![(0, vec![1, 2]), (1, vec![3, 0])])
+    /// );
+    /// assert_eq!(table["bbb"], 3);
+    ///
+    /// // Parsers with their own state can run inside the function.
+    /// let depth = StateSlot::new();
+    /// let inner = prefix("x").map_with_state(&depth, |depth: &mut usize, _| *depth += 1);
+    /// let outer = prefix("y").map_with_state(&ids, |ids, y| {
+    ///     let mut count = 0;
+    ///     inner.parse_with_state(&depth, &mut count, "x");
+    ///     ids.insert(y, count);
+    /// });
+    /// let mut table = HashMap::new();
+    ///
+    /// outer.parse_with_state(&ids, &mut table, "y");
+    /// assert_eq!(table["y"], 1);
+    /// ```
+    fn map_with_state<S, U, F: Fn(&mut S, Self::Output) -> U>(
+        self,
+        slot: &StateSlot<S>,
+        f: F,
+    ) -> ParserImpl<MapWithState<Self, F, S>>
+    where
+        Self: Sized,
+    {
+        ParserImpl(MapWithState {
+            parser: self,
+            f,
+            slot: slot.clone(),
+        })
+    }
+
     /// Parses `self` without consuming any input.
     ///
     /// # Returns
@@ -281,7 +581,10 @@ pub trait Parser: Sized {
     ///
     /// assert_eq!(any.then_zip(any).run_parser("hello"), Some(('h', 'h')));
     /// ```
-    fn look_ahead(self) -> ParserImpl<LookAhead<Self>> {
+    fn look_ahead(self) -> ParserImpl<LookAhead<Self>>
+    where
+        Self: Sized,
+    {
         ParserImpl(LookAhead { parser: self })
     }
 
@@ -300,30 +603,646 @@ pub trait Parser: Sized {
     ///
     /// assert_eq!(int.run_parser("abcedf.;.123abc"), Some(123));
     /// ```
-    fn anywhere(self) -> ParserImpl<Anywhere<Self>> {
+    fn anywhere(self) -> ParserImpl<Anywhere<Self>>
+    where
+        Self: Sized,
+    {
         ParserImpl(Anywhere { parser: self })
     }
+
+    /// Optionally parses `self`. Always succeeds.
+    ///
+    /// # Returns
+    /// A new `Parser` that produces `Some` with the output of `self` or `None` if it failed.
+    ///
+    /// # Examples
+    /// ```
+    /// use aoc_utils::parser::*;
+    ///
+    /// let sign = prefix("-").optional();
+    ///
+    /// assert_eq!(sign.parse("-5"), Some((Some("-"), "5")));
+    /// assert_eq!(sign.parse("5"), Some((None, "5")));
+    /// ```
+    fn optional(self) -> ParserImpl<Optional<Self>>
+    where
+        Self: Sized,
+    {
+        ParserImpl(Optional { parser: self })
+    }
+
+    /// Parses `open`, then `self` and then `close`, and returns the result of `self`.
+    ///
+    /// # Arguments
+    /// * `open`: The parser before `self`.
+    /// * `close`: The parser after `self`.
+    ///
+    /// # Returns
+    /// A new `Parser` that produces the output of `self`.
+    ///
+    /// # Examples
+    /// ```
+    /// use aoc_utils::parser::*;
+    ///
+    /// let list = number().sep_by(prefix(","), ..).between(prefix("["), prefix("]"));
+    ///
+    /// assert_eq!(list.run_parser("[1,2,3]"), Some(vec![1, 2, 3]));
+    /// assert_eq!(list.run_parser("[1,2,3"), None);
+    /// ```
+    fn between<Q: Parser<'a, I>, S: Parser<'a, I>>(
+        self,
+        open: Q,
+        close: S,
+    ) -> ParserImpl<Between<Self, Q, S>>
+    where
+        Self: Sized,
+    {
+        ParserImpl(Between {
+            parser: self,
+            open,
+            close,
+        })
+    }
+
+    /// Parses exactly `n` occurrences of `self` and collects the results into a `Vec<Self::Output>`.
+    ///
+    /// # Arguments
+    /// * `n`: The number of ocurrences.
+    ///
+    /// # Returns
+    /// A new `Parser` that produces a `Vec<Self::Output>`.
+    ///
+    /// # Examples
+    /// ```
+    /// use aoc_utils::parser::*;
+    ///
+    /// let pair = any_char().count(2);
+    ///
+    /// assert_eq!(pair.run_parser("abc"), Some(vec!['a', 'b']));
+    /// assert_eq!(pair.run_parser("a"), None);
+    /// ```
+    fn count(self, n: usize) -> ParserImpl<Repeat<Self, RangeInclusive<usize>>>
+    where
+        Self: Sized,
+    {
+        self.repeat(n..=n)
+    }
+
+    /// Parses occurrences of `self` until `end` succeeds and collects the results into a `Vec<Self::Output>`.
+    ///
+    /// The input consumed by `end` is discarded. The parser fails if `self` succeeds without
+    /// consuming input before `end` succeeds, as it would repeat forever.
+    ///
+    /// # Arguments
+    /// * `end`: The parser that stops the repetition.
+    ///
+    /// # Returns
+    /// A new `Parser` that produces a `Vec<Self::Output>`.
+    ///
+    /// # Examples
+    /// ```
+    /// use aoc_utils::parser::*;
+    ///
+    /// let comment = prefix("/*") >> any_char().many_till(prefix("*/"));
+    ///
+    /// assert_eq!(comment.parse("/*ab*/c"), Some((vec!['a', 'b'], "c")));
+    /// assert_eq!(comment.parse("/*ab"), None);
+    ///
+    /// let words = take_while(char::is_alphabetic).many_till(prefix("."));
+    ///
+    /// assert_eq!(words.parse("ab."), Some((vec!["ab"], "")));
+    /// // `take_while` stops before the space without consuming it.
+    /// assert_eq!(words.parse("ab cd."), None);
+    /// ```
+    fn many_till<Q: Parser<'a, I>>(self, end: Q) -> ParserImpl<ManyTill<Self, Q>>
+    where
+        Self: Sized,
+    {
+        ParserImpl(ManyTill { parser: self, end })
+    }
+
+    /// Parses a range of occurrences of `self` separated by `rhs`, allowing a trailing `rhs`,
+    /// and collects the results into a `Vec<Self::Output>`.
+    ///
+    /// # Arguments
+    /// * `rhs`: The separator.
+    /// * `range`: The range for the number of ocurrences.
+    ///
+    /// # Returns
+    /// A new `Parser` that produces a `Vec<Self::Output>`.
+    ///
+    /// # Examples
+    /// ```
+    /// use aoc_utils::parser::*;
+    ///
+    /// let digits = digit().sep_end_by(prefix(","), 1..);
+    ///
+    /// assert_eq!(digits.parse("1,2,3,abc"), Some((vec!['1', '2', '3'], "abc")));
+    /// assert_eq!(digits.parse("1,2,3abc"), Some((vec!['1', '2', '3'], "abc")));
+    /// assert_eq!(digits.parse(",abc"), None);
+    /// ```
+    fn sep_end_by<Q: Parser<'a, I>, R: RangeBounds<usize>>(
+        self,
+        rhs: Q,
+        range: R,
+    ) -> ParserImpl<SepEndBy<Self, Q, R>>
+    where
+        Self: Sized,
+    {
+        ParserImpl(SepEndBy {
+            sep_by: SepBy {
+                parser: self,
+                rhs,
+                range,
+            },
+        })
+    }
+
+    /// Parses a range of occurrences of `self`, each one followed by `rhs`,
+    /// and collects the results into a `Vec<Self::Output>`.
+    ///
+    /// # Arguments
+    /// * `rhs`: The terminator.
+    /// * `range`: The range for the number of ocurrences.
+    ///
+    /// # Returns
+    /// A new `Parser` that produces a `Vec<Self::Output>`.
+    ///
+    /// # Examples
+    /// ```
+    /// use aoc_utils::parser::*;
+    ///
+    /// let statements = number().end_by(prefix(";"), ..);
+    ///
+    /// assert_eq!(statements.parse("1;2;3"), Some((vec![1, 2], "3")));
+    /// ```
+    fn end_by<Q: Parser<'a, I>, R: RangeBounds<usize>>(
+        self,
+        rhs: Q,
+        range: R,
+    ) -> ParserImpl<Repeat<ThenIgnore<Self, Q>, R>>
+    where
+        Self: Sized,
+    {
+        ParserImpl(Repeat {
+            parser: ThenIgnore { parser: self, rhs },
+            range,
+        })
+    }
+
+    /// Parses any number of occurrences of `self` discarding the results. Always succeeds.
+    ///
+    /// # Returns
+    /// A new `Parser` that produces `()`.
+    ///
+    /// # Examples
+    /// ```
+    /// use aoc_utils::parser::*;
+    ///
+    /// let padded = prefix(" ").skip_many() >> number();
+    ///
+    /// assert_eq!(padded.run_parser("   42"), Some(42));
+    /// assert_eq!(padded.run_parser("42"), Some(42));
+    /// ```
+    fn skip_many(self) -> ParserImpl<SkipMany<Self>>
+    where
+        Self: Sized,
+    {
+        ParserImpl(SkipMany { parser: self })
+    }
+
+    /// Parses `self` and replaces its output with `value`.
+    ///
+    /// # Arguments
+    /// * `value`: The value produced on success.
+    ///
+    /// # Returns
+    /// A new `Parser` that produces values of type `T`.
+    ///
+    /// # Examples
+    /// ```
+    /// use aoc_utils::parser::*;
+    ///
+    /// let light = prefix("#").value(true) | prefix(".").value(false);
+    ///
+    /// assert_eq!(light.repeat(..).run_parser("#.#"), Some(vec![true, false, true]));
+    /// ```
+    fn value<T: Clone>(self, value: T) -> ParserImpl<Value<Self, T>>
+    where
+        Self: Sized,
+    {
+        ParserImpl(Value {
+            parser: self,
+            value,
+        })
+    }
+
+    /// Parses `self` and then discards any spaces and tabs that follow it.
+    ///
+    /// Building a grammar out of lexemes defines the whitespace handling in a single place,
+    /// instead of spelling out the spaces in every prefix.
+    ///
+    /// # Returns
+    /// A new `Parser` that produces the output of `self`.
+    ///
+    /// # Examples
+    /// ```
+    /// use aoc_utils::parser::*;
+    ///
+    /// let columns = spaces() >> number().lexeme().repeat(..);
+    ///
+    /// assert_eq!(columns.run_parser("  12   3 45  "), Some(vec![12, 3, 45]));
+    /// ```
+    fn lexeme(self) -> ParserImpl<Lexeme<Self>>
+    where
+        Self: Sized,
+    {
+        ParserImpl(Lexeme { parser: self })
+    }
+
+    /// Parses `self` and returns the slice of input it consumed, discarding its output.
+    ///
+    /// # Returns
+    /// A new `Parser` that produces a `&str` borrowed from the input.
+    ///
+    /// # Examples
+    /// ```
+    /// use aoc_utils::parser::*;
+    ///
+    /// let range = (number() + (prefix("-") >> number())).recognize();
+    ///
+    /// assert_eq!(range.run_parser("12-345 abc"), Some("12-345"));
+    /// ```
+    fn recognize(self) -> ParserImpl<Recognize<Self>>
+    where
+        Self: Sized,
+    {
+        ParserImpl(Recognize { parser: self })
+    }
+
+    /// Parses `self` and pairs its output with the range of bytes it consumed.
+    ///
+    /// Inside [`run_parser`](Parser::run_parser) or [`iter_matches`](Parser::iter_matches) the range
+    /// is relative to the whole input, otherwise it is relative to the slice given to this parser.
+    /// [`line_col`] turns the offsets into lines and columns.
+    ///
+    /// # Returns
+    /// A new `Parser` that produces a tuple `(Self::Output, Range<usize>)`.
+    ///
+    /// # Examples
+    /// ```
+    /// use aoc_utils::parser::*;
+    ///
+    /// let numbers = (number().with_span() << take_while(|c| !c.is_ascii_digit())).repeat(..);
+    ///
+    /// assert_eq!(
+    ///     numbers.run_parser("467..114\n..35"),
+    ///     Some(vec![(467, 0..3), (114, 5..8), (35, 11..13)])
+    /// );
+    /// ```
+    fn with_span(self) -> ParserImpl<WithSpan<Self>>
+    where
+        Self: Sized,
+    {
+        ParserImpl(WithSpan { parser: self })
+    }
+
+    /// Records the attempts of the parser under `label` in the trace of the run.
+    ///
+    /// # Arguments
+    /// * `label`: The name of the parser in the trace.
+    ///
+    /// # Returns
+    /// A new `Parser` that produces the same values as `self`.
+    ///
+    /// # Examples
+    /// ```
+    /// use aoc_utils::parser::*;
+    ///
+    /// let op = one_of("+*").trace("op");
+    /// let (_, trace) = (prefix("+").anywhere().look_ahead() >> op).run_traced("1 2 +");
+    ///
+    /// assert_eq!(trace.children[0].label, "op");
+    /// assert_eq!(trace.children[0].consumed, None);
+    /// ```
+    fn trace(self, label: &'static str) -> ParserImpl<Traced<Self>>
+    where
+        Self: Sized,
+    {
+        ParserImpl(Traced {
+            parser: self,
+            label,
+        })
+    }
+
+    /// Gives a name to the parser, so [`describe`](Parser::describe) refers to it by name and
+    /// writes its definition as a rule of its own.
+    ///
+    /// Labels are also needed to describe recursive grammars.
+    ///
+    /// # Arguments
+    /// * `name`: The name of the rule.
+    ///
+    /// # Returns
+    /// A new `Parser` that produces the same values as `self`.
+    ///
+    /// # Examples
+    /// ```
+    /// use aoc_utils::parser::*;
+    ///
+    /// let list = recursive(|list| {
+    ///     (number().value(()) | list.sep_by(prefix(","), ..).between(prefix("["), prefix("]")).value(()))
+    ///         .label("item")
+    /// });
+    ///
+    /// assert_eq!(list.describe(), "item = [0-9]+ | \"[\" (item (\",\" item)*)? \"]\" ;");
+    /// ```
+    fn label(self, name: &'static str) -> ParserImpl<Label<Self>>
+    where
+        Self: Sized,
+    {
+        ParserImpl(Label { parser: self, name })
+    }
+
+    /// Parses one or more occurrences of `self` separated by `op` and combines them from the left
+    /// using `f`, as for left-associative operators.
+    ///
+    /// # Arguments
+    /// * `op`: The operator parser.
+    /// * `f`: A closure that takes the left operand, the output of `op` and the right operand and combines them.
+    ///
+    /// # Returns
+    /// A new `Parser` that produces values of type `Self::Output`.
+    ///
+    /// # Examples
+    /// ```
+    /// use aoc_utils::parser::*;
+    ///
+    /// let diff = number().map(|n| n as i64).chainl1(prefix("-"), |a, _, b| a - b);
+    ///
+    /// assert_eq!(diff.run_parser("10-3-2"), Some(5));
+    /// ```
+    fn chainl1<Q, F>(self, op: Q, f: F) -> ParserImpl<ChainL1<Self, Q, F>>
+    where
+        Self: Sized,
+        Q: Parser<'a, I>,
+        F: Fn(Self::Output, Q::Output, Self::Output) -> Self::Output,
+    {
+        ParserImpl(ChainL1 {
+            parser: self,
+            op,
+            f,
+        })
+    }
+
+    /// Parses one or more occurrences of `self` separated by `op` and combines them from the right
+    /// using `f`, as for right-associative operators.
+    ///
+    /// # Arguments
+    /// * `op`: The operator parser.
+    /// * `f`: A closure that takes the left operand, the output of `op` and the right operand and combines them.
+    ///
+    /// # Returns
+    /// A new `Parser` that produces values of type `Self::Output`.
+    ///
+    /// # Examples
+    /// ```
+    /// use aoc_utils::parser::*;
+    ///
+    /// let pow = number().chainr1(prefix("^"), |a, _, b| a.pow(b as u32));
+    ///
+    /// assert_eq!(pow.run_parser("2^3^2"), Some(512));
+    /// ```
+    fn chainr1<Q, F>(self, op: Q, f: F) -> ParserImpl<ChainR1<Self, Q, F>>
+    where
+        Self: Sized,
+        Q: Parser<'a, I>,
+        F: Fn(Self::Output, Q::Output, Self::Output) -> Self::Output,
+    {
+        ParserImpl(ChainR1 {
+            parser: self,
+            op,
+            f,
+        })
+    }
+
+    /// Caches the results of `self` by position in the input, so parsing again at the same position
+    /// during the same `run_parser` call returns the stored result instead of repeating the work.
+    ///
+    /// Clones of the returned parser share the cache, so a rule can be memoised once and used in many places.
+    /// Useful for grammars that backtrack a lot, which become linear-time.
+    /// Outside of `run_parser`, results are not cached.
+    ///
+    /// # Returns
+    /// A new `Parser` that produces values of type `Self::Output`.
+    ///
+    /// # Examples
+    /// ```
+    /// use aoc_utils::parser::*;
+    ///
+    /// let input = format!("{}x{}", "a".repeat(100), "c".repeat(100));
+    ///
+    /// // Without `memo` this grammar takes exponential time, as each alternative parses `s` again.
+    /// let s = recursive(|s| {
+    ///     let s = s.memo();
+    ///     (prefix("a") >> s.clone() << prefix("b"))
+    ///         | (prefix("a") >> s << prefix("c"))
+    ///         | prefix("x")
+    /// });
+    ///
+    /// assert_eq!(s.run_parser(&input), Some("x"));
+    /// ```
+    fn memo(self) -> ParserImpl<Memo<Self, Self::Output>>
+    where
+        Self: Sized,
+        Self::Output: Clone,
+    {
+        ParserImpl(Memo {
+            parser: self,
+            cache: Rc::default(),
+        })
+    }
+
+    /// Erases the type of `self`, so it can be stored along parsers of different types.
+    ///
+    /// # Returns
+    /// A new `BoxedParser` that produces values of type `Self::Output`.
+    ///
+    /// # Examples
+    /// ```
+    /// use aoc_utils::parser::*;
+    ///
+    /// let mut fields: Vec<BoxedParser<usize>> = vec![number().boxed()];
+    /// fields.push(prefix("x").value(0).boxed());
+    ///
+    /// let field = choice(fields);
+    ///
+    /// assert_eq!(field.run_parser("12"), Some(12));
+    /// assert_eq!(field.run_parser("x"), Some(0));
+    /// ```
+    fn boxed(self) -> BoxedParser<'a, Self::Output, I>
+    where
+        Self: Sized + 'a,
+    {
+        ParserImpl(Boxed(Rc::new(self)))
+    }
+
+    /// Lifts `self` into a nondeterministic parser that produces its single parse.
+    ///
+    /// # Returns
+    /// A new `NdParser` that produces values of type `Self::Output`.
+    ///
+    /// # Examples
+    /// ```
+    /// use aoc_utils::parser::*;
+    ///
+    /// assert_eq!(number().nondet().parse_all("12a"), vec![(12, "a")]);
+    /// assert_eq!(number().nondet().parse_all("a12"), vec![]);
+    /// ```
+    fn nondet(self) -> Nondet<Self>
+    where
+        Self: Sized,
+    {
+        Nondet { parser: self }
+    }
+
+    /// Lazily finds every non-overlapping match of `self` in the input, skipping the text between them.
+    ///
+    /// Matches that don't consume any input are skipped.
+    ///
+    /// # Arguments
+    /// * `input`: The input to search.
+    ///
+    /// # Returns
+    /// An `Iterator` over the parsed values and the range of the input each one was parsed from, in bytes for `str`.
+    ///
+    /// # Examples
+    /// ```
+    /// use aoc_utils::parser::*;
+    ///
+    /// let ints = number().iter_matches("x=12, y=345;").collect::<Vec<_>>();
+    ///
+    /// assert_eq!(ints, vec![(12, 2..4), (345, 8..11)]);
+    /// assert_eq!(number().iter_matches("no numbers").next(), None);
+    /// ```
+    fn iter_matches(&self, input: &'a I) -> Matches<'_, 'a, Self, I>
+    where
+        Self: Sized,
+    {
+        Matches {
+            parser: self,
+            input,
+            offset: 0,
+            context: Context::new(input),
+        }
+    }
 }
 
+/// Parses a sequence of parsers into a flat tuple, discarding the outputs of the ones marked with `~`.
+///
+/// If only one output is kept it is returned on its own instead of inside a tuple.
+///
+/// # Examples
+/// ```
+/// use aoc_utils::parser::*;
+///
+/// let point = seq!(~prefix("("), number(), ~prefix(","), number(), ~prefix(")"));
+/// assert_eq!(point.run_parser("(3,4)"), Some((3, 4)));
+///
+/// let id = seq!(~prefix("#"), number());
+/// assert_eq!(id.run_parser("#12"), Some(12));
+/// ```
+#[macro_export]
+macro_rules! seq {
+    (@acc [$($p:expr,)*] [$($pat:tt)*] [$out:ident]) => {
+        $crate::parser::Parser::map(($($p,)*), |($($pat)*)| $out)
+    };
+    (@acc [$($p:expr,)*] [$($pat:tt)*] [$($out:ident)*]) => {
+        $crate::parser::Parser::map(($($p,)*), |($($pat)*)| ($($out,)*))
+    };
+    (@acc [$($p:expr,)*] [$($pat:tt)*] [$($out:ident)*] ~ $q:expr $(, $($rest:tt)*)?) => {
+        $crate::seq!(@acc [$($p,)* $q,] [$($pat)* _,] [$($out)*] $($($rest)*)?)
+    };
+    (@acc [$($p:expr,)*] [$($pat:tt)*] [$($out:ident)*] $q:expr $(, $($rest:tt)*)?) => {
+        $crate::seq!(@acc [$($p,)* $q,] [$($pat)* x,] [$($out)* x] $($($rest)*)?)
+    };
+    ($($rest:tt)+) => {
+        $crate::seq!(@acc [] [] [] $($rest)+)
+    };
+}
+
+pub use crate::seq;
+pub use aoc_derive::{FromAocStr, scan};
+
 /// A wrapper for parsers that enables operator overloading.
+///
+/// The supported operators are:
+/// * `a | b`: [`Parser::or`].
+/// * `a + b`: [`Parser::then_zip`].
+/// * `a >> b`: [`Parser::ignored_then`].
+/// * `a << b`: [`Parser::then_ignore`].
+/// * `!a`: [`not_followed_by`].
+/// * `a * n`: [`Parser::count`] if `n` is a `usize` or [`Parser::repeat`] if it is a range.
+///
+/// # Examples
+/// ```
+/// use aoc_utils::parser::*;
+///
+/// let hex = prefix("#") >> one_of("0123456789abcdef") * 6 << !alnum();
+///
+/// assert_eq!(hex.run_parser("#00ff00"), Some(vec!['0', '0', 'f', 'f', '0', '0']));
+/// assert_eq!(hex.run_parser("#00ff00a"), None);
+/// assert_eq!((digit() * (1..)).run_parser("12a"), Some(vec!['1', '2']));
+/// ```
 #[derive(Clone, Copy)]
 pub struct ParserImpl<P>(P);
 
-impl<P: Parser> Parser for ParserImpl<P> {
+/// Wraps a parser, like a function or a closure, so it can be combined with operators.
+///
+/// # Arguments
+/// * `parser`: The parser to wrap.
+///
+/// # Returns
+/// The same `Parser` wrapped in a `ParserImpl`.
+///
+/// # Examples
+/// ```
+/// use aoc_utils::parser::*;
+///
+/// fn list(input: &str) -> Option<(Vec<usize>, &str)> {
+///     (prefix("[") >> number().sep_by(prefix(","), ..) << prefix("]")).parse(input)
+/// }
+///
+/// let lists = p(list) + (prefix(" ") >> p(list));
+///
+/// assert_eq!(lists.run_parser("[1,2] [3]"), Some((vec![1, 2], vec![3])));
+/// ```
+pub fn p<'a, I: Input + ?Sized, P: Parser<'a, I>>(parser: P) -> ParserImpl<P> {
+    ParserImpl(parser)
+}
+
+impl<'a, I: Input + ?Sized, P: Parser<'a, I>> Parser<'a, I> for ParserImpl<P> {
     type Output = P::Output;
 
-    fn parse<'a>(&self, input: &'a str) -> Option<(Self::Output, &'a str)> {
+    fn parse(&self, input: &'a I) -> Option<(Self::Output, &'a I)> {
         self.0.parse(input)
     }
+
+    fn describe_into(&self, rules: &mut Rules) -> Ebnf {
+        self.0.describe_into(rules)
+    }
 }
 
+/// Parser that collects the chars produced by `P` into a `String`.
+pub type CollectString<P> = ParserImpl<Map<P, fn(Vec<char>) -> String>>;
+
 /// Allows to create strings from parsers
-pub trait StrParser: Parser<Output = Vec<char>> + Sized {
-    fn collect_string(self) -> impl Parser<Output = String>;
+pub trait StrParser<'a>: Parser<'a, Output = Vec<char>> + Sized {
+    fn collect_string(self) -> CollectString<Self>;
 }
 
-impl<P: Parser<Output = Vec<char>>> StrParser for P {
-    fn collect_string(self) -> impl Parser<Output = String> {
+impl<'a, P: Parser<'a, Output = Vec<char>>> StrParser<'a> for P {
+    fn collect_string(self) -> CollectString<Self> {
         self.map(|chars| chars.into_iter().collect())
     }
 }
@@ -0,0 +1,552 @@
+//! Nondeterministic parsers, which produce every possible parse instead of only the first one.
+//!
+//! `Parser::or` and `Parser::repeat` commit to the first alternative that succeeds, so ambiguous grammars
+//! like `a+ a` fail even if the input matches them. An `NdParser` keeps every alternative alive and returns
+//! all the ways the input can be parsed.
+//!
+//! Deterministic parsers are lifted into this mode with [`Parser::nondet`].
+
+use super::{Ebnf, Parser, Rules, trace};
+
+use std::ops::{Bound, RangeBounds};
+
+/// A nondeterministic parser takes string slices as input and produces every possible pair
+/// of a value of type `Output` and the remaining unconsumed string slice.
+pub trait NdParser<'a> {
+    type Output;
+
+    /// Parses the input string slice in every possible way.
+    ///
+    /// # Arguments
+    /// * `input`: The string slice to parse.
+    ///
+    /// # Returns
+    /// A `Vec<(Self::Output, &str)>` with every parsed value and its remaining string slice,
+    /// empty on failure.
+    fn parse_all(&self, input: &'a str) -> Vec<(Self::Output, &'a str)>;
+
+    /// Describes the parser as an EBNF-like expression, adding the labeled parsers it uses to `rules`.
+    ///
+    /// Every nondeterministic parser in this module implements it. The default implementation
+    /// describes the parser by the name of its type, as in `<Name>`.
+    ///
+    /// # Arguments
+    /// * `rules`: The rules found so far.
+    ///
+    /// # Returns
+    /// The [`Ebnf`] expression of the parser.
+    fn describe_into(&self, rules: &mut Rules) -> Ebnf {
+        let _ = rules;
+        Ebnf::special(&trace::type_label::<Self>())
+    }
+
+    /// Renders the grammar accepted by the parser as EBNF-like text, in the notation of
+    /// [`Parser::describe`].
+    ///
+    /// # Returns
+    /// A `String` with the grammar.
+    ///
+    /// # Examples
+    /// ```
+    /// use aoc_utils::parser::*;
+    ///
+    /// let a = prefix("a").nondet();
+    /// let a_plus_a = a.repeat(1..).then_ignore(a).or(prefix("b").nondet().repeat(1..=1));
+    ///
+    /// assert_eq!(a_plus_a.describe(), "\"a\"+ \"a\" | \"b\"");
+    /// ```
+    fn describe(&self) -> String {
+        let mut rules = Rules::default();
+        let top = self.describe_into(&mut rules);
+        rules.render(top)
+    }
+
+    /// Runs the parser on the given input and returns the values of the parses that consume the whole input.
+    ///
+    /// # Arguments
+    /// * `input`: The string slice to parse.
+    ///
+    /// # Returns
+    /// A `Vec<Self::Output>` with every complete parse.
+    ///
+    /// # Examples
+    /// ```
+    /// use aoc_utils::parser::*;
+    ///
+    /// let a = prefix("a").nondet();
+    /// let aa = prefix("aa").nondet();
+    ///
+    /// assert_eq!(a.or(aa).repeat(..).run_all("aaa").len(), 3);
+    /// ```
+    fn run_all(&self, input: &'a str) -> Vec<Self::Output> {
+        self.parse_all(input)
+            .into_iter()
+            .filter_map(|(output, remaining)| remaining.is_empty().then_some(output))
+            .collect()
+    }
+
+    /// Checks whether any parse consumes the whole input.
+    ///
+    /// # Arguments
+    /// * `input`: The string slice to parse.
+    ///
+    /// # Returns
+    /// `true` if the input can be completely parsed.
+    ///
+    /// # Examples
+    /// ```
+    /// use aoc_utils::parser::*;
+    ///
+    /// let a = prefix("a").nondet();
+    /// let a_plus_a = a.repeat(1..).then_ignore(a);
+    ///
+    /// assert!(a_plus_a.matches("aaa"));
+    /// assert!(!a_plus_a.matches("a"));
+    ///
+    /// // The deterministic version consumes every `a` in the repetition and then fails.
+    /// assert_eq!((prefix("a").repeat(1..) << prefix("a")).run_parser("aaa"), None);
+    /// ```
+    fn matches(&self, input: &'a str) -> bool {
+        self.parse_all(input)
+            .iter()
+            .any(|(_, remaining)| remaining.is_empty())
+    }
+
+    /// Combines two parsers, keeping the parses of both.
+    ///
+    /// # Arguments
+    /// * `rhs`: The other alternative.
+    ///
+    /// # Returns
+    /// A new `NdParser` that produces the parses of `self` followed by the parses of `rhs`.
+    ///
+    /// # Examples
+    /// ```
+    /// use aoc_utils::parser::*;
+    ///
+    /// let word = prefix("ab").nondet().or(prefix("a").nondet());
+    ///
+    /// assert_eq!(word.parse_all("abc"), vec![("ab", "c"), ("a", "bc")]);
+    /// ```
+    fn or<Q: NdParser<'a, Output = Self::Output>>(self, rhs: Q) -> NdOr<Self, Q>
+    where
+        Self: Sized,
+    {
+        NdOr { lhs: self, rhs }
+    }
+
+    /// Transforms the output of every parse using the provided function `f`.
+    ///
+    /// # Arguments
+    /// * `f`: A closure that takes the parsed value `Self::Output` and returns a new value `U`.
+    ///
+    /// # Returns
+    /// A new `NdParser` that produces values of type `U`.
+    ///
+    /// # Examples
+    /// ```
+    /// use aoc_utils::parser::*;
+    ///
+    /// let len = take_while(char::is_alphabetic).nondet().map(str::len);
+    ///
+    /// assert_eq!(len.run_all("abc"), vec![3]);
+    /// ```
+    fn map<U, F: Fn(Self::Output) -> U>(self, f: F) -> NdMap<Self, F>
+    where
+        Self: Sized,
+    {
+        NdMap { parser: self, f }
+    }
+
+    /// Parses `self`, then parses `rhs` after every parse of `self`, and combines their results into tuples.
+    ///
+    /// # Arguments
+    /// * `rhs`: The parser to run after `self`.
+    ///
+    /// # Returns
+    /// A new `NdParser` that produces tuples of `(Self::Output, Q::Output)`.
+    ///
+    /// # Examples
+    /// ```
+    /// use aoc_utils::parser::*;
+    ///
+    /// let digits = digit().nondet().repeat(1..);
+    /// let split = digits.clone().then_zip(digits);
+    ///
+    /// assert_eq!(split.run_all("123").len(), 2);
+    /// ```
+    fn then_zip<Q: NdParser<'a>>(self, rhs: Q) -> NdThenZip<Self, Q>
+    where
+        Self: Sized,
+        Self::Output: Clone,
+    {
+        NdThenZip { parser: self, rhs }
+    }
+
+    /// Parses `self`, then parses `rhs`, and returns the results of `rhs`.
+    ///
+    /// # Arguments
+    /// * `rhs`: The parser whose output will be returned.
+    ///
+    /// # Returns
+    /// A new `NdParser` that produces the outputs of `rhs`.
+    ///
+    /// # Examples
+    /// ```
+    /// use aoc_utils::parser::*;
+    ///
+    /// let id = prefix("#").nondet().ignored_then(number().nondet());
+    ///
+    /// assert_eq!(id.run_all("#12"), vec![12]);
+    /// ```
+    fn ignored_then<Q: NdParser<'a>>(self, rhs: Q) -> NdIgnoredThen<Self, Q>
+    where
+        Self: Sized,
+    {
+        NdIgnoredThen { parser: self, rhs }
+    }
+
+    /// Parses `self`, then parses `rhs`, and returns the results of `self`.
+    ///
+    /// # Arguments
+    /// * `rhs`: The parser whose output will be ignored.
+    ///
+    /// # Returns
+    /// A new `NdParser` that produces the outputs of `self`.
+    ///
+    /// # Examples
+    /// ```
+    /// use aoc_utils::parser::*;
+    ///
+    /// let id = number().nondet().then_ignore(prefix(";").nondet());
+    ///
+    /// assert_eq!(id.run_all("12;"), vec![12]);
+    /// ```
+    fn then_ignore<Q: NdParser<'a>>(self, rhs: Q) -> NdThenIgnore<Self, Q>
+    where
+        Self: Sized,
+    {
+        NdThenIgnore { parser: self, rhs }
+    }
+
+    /// Parses every possible number of occurrences of `self` inside a range.
+    ///
+    /// # Arguments
+    /// * `range`: The range for the number of ocurrences.
+    ///
+    /// # Returns
+    /// A new `NdParser` that produces a `Vec<Self::Output>` for every possible repetition.
+    ///
+    /// # Examples
+    /// ```
+    /// use aoc_utils::parser::*;
+    ///
+    /// let digits = digit().nondet().repeat(1..=2);
+    ///
+    /// assert_eq!(
+    ///     digits.parse_all("123"),
+    ///     vec![(vec!['1'], "23"), (vec!['1', '2'], "3")]
+    /// );
+    /// ```
+    fn repeat<R: RangeBounds<usize>>(self, range: R) -> NdRepeat<Self, R>
+    where
+        Self: Sized,
+        Self::Output: Clone,
+    {
+        NdRepeat {
+            parser: self,
+            range,
+        }
+    }
+}
+
+//////////////////////////////////////////////////
+//                    Nondet                    //
+//////////////////////////////////////////////////
+
+/// Nondeterministic parser that produces the single parse of a deterministic parser.
+#[derive(Clone, Copy)]
+pub struct Nondet<P> {
+    pub(super) parser: P,
+}
+
+impl<'a, P: Parser<'a>> NdParser<'a> for Nondet<P> {
+    type Output = P::Output;
+
+    fn parse_all(&self, input: &'a str) -> Vec<(Self::Output, &'a str)> {
+        self.parser.parse(input).into_iter().collect()
+    }
+
+    fn describe_into(&self, rules: &mut Rules) -> Ebnf {
+        self.parser.describe_into(rules)
+    }
+}
+
+////////////////////////////////////////////////
+//                    NdOr                    //
+////////////////////////////////////////////////
+
+/// Nondeterministic parser that produces the parses of two parsers.
+#[derive(Clone, Copy)]
+pub struct NdOr<P, Q> {
+    lhs: P,
+    rhs: Q,
+}
+
+impl<'a, P, Q> NdParser<'a> for NdOr<P, Q>
+where
+    P: NdParser<'a>,
+    Q: NdParser<'a, Output = P::Output>,
+{
+    type Output = P::Output;
+
+    fn parse_all(&self, input: &'a str) -> Vec<(Self::Output, &'a str)> {
+        let mut results = self.lhs.parse_all(input);
+        results.extend(self.rhs.parse_all(input));
+        results
+    }
+
+    fn describe_into(&self, rules: &mut Rules) -> Ebnf {
+        Ebnf::alternative([self.lhs.describe_into(rules), self.rhs.describe_into(rules)])
+    }
+}
+
+/////////////////////////////////////////////////
+//                    NdMap                    //
+/////////////////////////////////////////////////
+
+/// Nondeterministic parser that transforms the outputs of another parser using a function.
+#[derive(Clone, Copy)]
+pub struct NdMap<P, F> {
+    parser: P,
+    f: F,
+}
+
+impl<'a, U, P: NdParser<'a>, F: Fn(P::Output) -> U> NdParser<'a> for NdMap<P, F> {
+    type Output = U;
+
+    fn parse_all(&self, input: &'a str) -> Vec<(Self::Output, &'a str)> {
+        self.parser
+            .parse_all(input)
+            .into_iter()
+            .map(|(output, remaining)| ((self.f)(output), remaining))
+            .collect()
+    }
+
+    fn describe_into(&self, rules: &mut Rules) -> Ebnf {
+        self.parser.describe_into(rules)
+    }
+}
+
+/////////////////////////////////////////////////////
+//                    NdThenZip                    //
+/////////////////////////////////////////////////////
+
+/// Nondeterministic parser that runs a parser after every parse of another one.
+#[derive(Clone, Copy)]
+pub struct NdThenZip<P, Q> {
+    parser: P,
+    rhs: Q,
+}
+
+impl<'a, P, Q> NdParser<'a> for NdThenZip<P, Q>
+where
+    P: NdParser<'a>,
+    P::Output: Clone,
+    Q: NdParser<'a>,
+{
+    type Output = (P::Output, Q::Output);
+
+    fn parse_all(&self, input: &'a str) -> Vec<(Self::Output, &'a str)> {
+        self.parser
+            .parse_all(input)
+            .into_iter()
+            .flat_map(|(left, remaining)| {
+                self.rhs
+                    .parse_all(remaining)
+                    .into_iter()
+                    .map(move |(right, remaining)| ((left.clone(), right), remaining))
+            })
+            .collect()
+    }
+
+    fn describe_into(&self, rules: &mut Rules) -> Ebnf {
+        Ebnf::sequence([
+            self.parser.describe_into(rules),
+            self.rhs.describe_into(rules),
+        ])
+    }
+}
+
+/////////////////////////////////////////////////////////
+//                    NdIgnoredThen                    //
+/////////////////////////////////////////////////////////
+
+/// Nondeterministic parser that runs a parser after every parse of another one and returns the outputs of the second one.
+#[derive(Clone, Copy)]
+pub struct NdIgnoredThen<P, Q> {
+    parser: P,
+    rhs: Q,
+}
+
+impl<'a, P: NdParser<'a>, Q: NdParser<'a>> NdParser<'a> for NdIgnoredThen<P, Q> {
+    type Output = Q::Output;
+
+    fn parse_all(&self, input: &'a str) -> Vec<(Self::Output, &'a str)> {
+        self.parser
+            .parse_all(input)
+            .into_iter()
+            .flat_map(|(_, remaining)| self.rhs.parse_all(remaining))
+            .collect()
+    }
+
+    fn describe_into(&self, rules: &mut Rules) -> Ebnf {
+        Ebnf::sequence([
+            self.parser.describe_into(rules),
+            self.rhs.describe_into(rules),
+        ])
+    }
+}
+
+////////////////////////////////////////////////////////
+//                    NdThenIgnore                    //
+////////////////////////////////////////////////////////
+
+/// Nondeterministic parser that runs a parser after every parse of another one and returns the outputs of the first one.
+#[derive(Clone, Copy)]
+pub struct NdThenIgnore<P, Q> {
+    parser: P,
+    rhs: Q,
+}
+
+impl<'a, P, Q> NdParser<'a> for NdThenIgnore<P, Q>
+where
+    P: NdParser<'a>,
+    P::Output: Clone,
+    Q: NdParser<'a>,
+{
+    type Output = P::Output;
+
+    fn parse_all(&self, input: &'a str) -> Vec<(Self::Output, &'a str)> {
+        self.parser
+            .parse_all(input)
+            .into_iter()
+            .flat_map(|(output, remaining)| {
+                self.rhs
+                    .parse_all(remaining)
+                    .into_iter()
+                    .map(move |(_, remaining)| (output.clone(), remaining))
+            })
+            .collect()
+    }
+
+    fn describe_into(&self, rules: &mut Rules) -> Ebnf {
+        Ebnf::sequence([
+            self.parser.describe_into(rules),
+            self.rhs.describe_into(rules),
+        ])
+    }
+}
+
+////////////////////////////////////////////////////
+//                    NdRepeat                    //
+////////////////////////////////////////////////////
+
+/// Nondeterministic parser that parses every possible number of occurrences of another parser.
+#[derive(Clone, Copy)]
+pub struct NdRepeat<P, R> {
+    parser: P,
+    range: R,
+}
+
+impl<'a, P, R> NdParser<'a> for NdRepeat<P, R>
+where
+    P: NdParser<'a>,
+    P::Output: Clone,
+    R: RangeBounds<usize>,
+{
+    type Output = Vec<P::Output>;
+
+    fn parse_all(&self, input: &'a str) -> Vec<(Self::Output, &'a str)> {
+        let min = match self.range.start_bound() {
+            Bound::Included(&n) => n,
+            Bound::Excluded(&n) => n + 1,
+            Bound::Unbounded => 0,
+        };
+
+        let max = match self.range.end_bound() {
+            Bound::Included(&n) => Some(n),
+            Bound::Excluded(&n) => Some(n - 1),
+            Bound::Unbounded => None,
+        };
+
+        let mut results = vec![];
+        let mut frontier = vec![(vec![], input)];
+        let mut count = 0;
+
+        while !frontier.is_empty() {
+            if count >= min {
+                results.extend(frontier.iter().cloned());
+            }
+
+            if max.is_some_and(|m| count >= m) {
+                break;
+            }
+
+            frontier = frontier
+                .into_iter()
+                .flat_map(|(items, rem)| {
+                    self.parser
+                        .parse_all(rem)
+                        .into_iter()
+                        .filter(move |(_, remaining)| remaining.len() < rem.len())
+                        .map(move |(output, remaining)| {
+                            let mut items = items.clone();
+                            items.push(output);
+                            (items, remaining)
+                        })
+                })
+                .collect();
+            count += 1;
+        }
+
+        results
+    }
+
+    fn describe_into(&self, rules: &mut Rules) -> Ebnf {
+        self.parser.describe_into(rules).repeat(&self.range)
+    }
+}
+
+////////////////////////////////////////////////////
+//                    Closures                    //
+////////////////////////////////////////////////////
+
+/// Implementing `NdParser<T>` for closures allows to define recursive nondeterministic parsers.
+///
+/// # Examples
+/// ```
+/// use aoc_utils::parser::*;
+///
+/// // Palindromes over `a` and `b`: an ambiguous grammar because the middle is unknown.
+/// fn palindrome(input: &str) -> Vec<((), &str)> {
+///     let a = prefix("a").nondet();
+///     let b = prefix("b").nondet();
+///
+///     a.map(|_| ())
+///         .or(b.map(|_| ()))
+///         .or(a.ignored_then(palindrome).then_ignore(a))
+///         .or(b.ignored_then(palindrome).then_ignore(b))
+///         .parse_all(input)
+/// }
+///
+/// assert!(palindrome.matches("abbba"));
+/// assert!(!palindrome.matches("abbaa"));
+/// ```
+impl<'a, T, F: Fn(&'a str) -> Vec<(T, &'a str)>> NdParser<'a> for F {
+    type Output = T;
+
+    fn parse_all(&self, input: &'a str) -> Vec<(Self::Output, &'a str)> {
+        self(input)
+    }
+}
@@ -1,8 +1,11 @@
 use super::{Parser, ParserImpl, combinator::*};
 
-use std::ops::{Add, BitOr, Shl, Shr};
+use std::ops::{
+    Add, BitOr, Mul, Not, Range, RangeFrom, RangeFull, RangeInclusive, RangeTo, RangeToInclusive,
+    Shl, Shr,
+};
 
-impl<P: Parser, Q: Parser<Output = P::Output>> BitOr<Q> for ParserImpl<P> {
+impl<'a, P: Parser<'a>, Q: Parser<'a, Output = P::Output>> BitOr<Q> for ParserImpl<P> {
     type Output = ParserImpl<Or<P, Q>>;
 
     fn bitor(self, rhs: Q) -> Self::Output {
@@ -10,7 +13,7 @@ impl<P: Parser, Q: Parser<Output = P::Output>> BitOr<Q> for ParserImpl<P> {
     }
 }
 
-impl<P: Parser, Q: Parser> Add<Q> for ParserImpl<P> {
+impl<'a, P: Parser<'a>, Q: Parser<'a>> Add<Q> for ParserImpl<P> {
     type Output = ParserImpl<ThenZip<P, Q>>;
 
     fn add(self, rhs: Q) -> Self::Output {
@@ -21,7 +24,7 @@ impl<P: Parser, Q: Parser> Add<Q> for ParserImpl<P> {
     }
 }
 
-impl<P: Parser, Q: Parser> Shr<Q> for ParserImpl<P> {
+impl<'a, P: Parser<'a>, Q: Parser<'a>> Shr<Q> for ParserImpl<P> {
     type Output = ParserImpl<IgnoredThen<P, Q>>;
 
     fn shr(self, rhs: Q) -> Self::Output {
@@ -32,7 +35,7 @@ impl<P: Parser, Q: Parser> Shr<Q> for ParserImpl<P> {
     }
 }
 
-impl<P: Parser, Q: Parser> Shl<Q> for ParserImpl<P> {
+impl<'a, P: Parser<'a>, Q: Parser<'a>> Shl<Q> for ParserImpl<P> {
     type Output = ParserImpl<ThenIgnore<P, Q>>;
 
     fn shl(self, rhs: Q) -> Self::Output {
@@ -42,3 +45,46 @@ impl<P: Parser, Q: Parser> Shl<Q> for ParserImpl<P> {
         })
     }
 }
+
+impl<'a, P: Parser<'a>> Not for ParserImpl<P> {
+    type Output = ParserImpl<NotFollowedBy<P>>;
+
+    fn not(self) -> Self::Output {
+        ParserImpl(NotFollowedBy { parser: self.0 })
+    }
+}
+
+impl<'a, P: Parser<'a>> Mul<usize> for ParserImpl<P> {
+    type Output = ParserImpl<Repeat<P, RangeInclusive<usize>>>;
+
+    fn mul(self, rhs: usize) -> Self::Output {
+        ParserImpl(Repeat {
+            parser: self.0,
+            range: rhs..=rhs,
+        })
+    }
+}
+
+macro_rules! mul_range {
+    ($($R:ty),+) => {$(
+        impl<'a, P: Parser<'a>> Mul<$R> for ParserImpl<P> {
+            type Output = ParserImpl<Repeat<P, $R>>;
+
+            fn mul(self, rhs: $R) -> Self::Output {
+                ParserImpl(Repeat {
+                    parser: self.0,
+                    range: rhs,
+                })
+            }
+        }
+    )+};
+}
+
+mul_range!(
+    Range<usize>,
+    RangeFrom<usize>,
+    RangeFull,
+    RangeInclusive<usize>,
+    RangeTo<usize>,
+    RangeToInclusive<usize>
+);
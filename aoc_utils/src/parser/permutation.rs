@@ -0,0 +1,315 @@
+//! Parsing of records whose fields can appear in any order.
+
+use super::{Ebnf, Parser, ParserImpl, Rules};
+
+use std::fmt;
+
+/// Error produced when the fields of a record don't match its definition.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum FieldError {
+    /// A required field doesn't appear.
+    Missing(&'static str),
+    /// A field appears more than once.
+    Duplicate(&'static str),
+}
+
+impl fmt::Display for FieldError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            FieldError::Missing(name) => write!(f, "missing field `{name}`"),
+            FieldError::Duplicate(name) => write!(f, "duplicate field `{name}`"),
+        }
+    }
+}
+
+impl std::error::Error for FieldError {}
+
+/// A named field of a record.
+pub trait FieldParser<'a> {
+    /// The output of the field parser.
+    type Parsed;
+    /// The value of the field once the record has been parsed.
+    type Value;
+
+    /// The name of the field, used when reporting errors.
+    fn name(&self) -> &'static str;
+
+    /// Parses the field.
+    fn parse_field(&self, input: &'a str) -> Option<(Self::Parsed, &'a str)>;
+
+    /// Produces the value of the field from its parsed output, if it appeared.
+    fn finish(&self, parsed: Option<Self::Parsed>) -> Result<Self::Value, FieldError>;
+
+    /// Describes the field, as in [`Parser::describe_into`].
+    fn describe_field(&self, rules: &mut Rules) -> Ebnf;
+}
+
+/////////////////////////////////////////////////////////
+//                    RequiredField                    //
+/////////////////////////////////////////////////////////
+
+/// Field that must appear exactly once.
+#[derive(Clone, Copy)]
+pub struct RequiredField<P> {
+    name: &'static str,
+    parser: P,
+}
+
+/// Creates a field that must appear exactly once in a [`permutation`].
+///
+/// # Arguments
+/// * `name`: The name of the field, used when reporting errors.
+/// * `parser`: The parser for the whole field, including its key.
+///
+/// # Returns
+/// A field whose value has the type of the output of `parser`.
+pub fn required_field<'a, P: Parser<'a>>(name: &'static str, parser: P) -> RequiredField<P> {
+    RequiredField { name, parser }
+}
+
+impl<'a, P: Parser<'a>> FieldParser<'a> for RequiredField<P> {
+    type Parsed = P::Output;
+    type Value = P::Output;
+
+    fn name(&self) -> &'static str {
+        self.name
+    }
+
+    fn parse_field(&self, input: &'a str) -> Option<(Self::Parsed, &'a str)> {
+        self.parser.parse(input)
+    }
+
+    fn finish(&self, parsed: Option<Self::Parsed>) -> Result<Self::Value, FieldError> {
+        parsed.ok_or(FieldError::Missing(self.name))
+    }
+
+    fn describe_field(&self, rules: &mut Rules) -> Ebnf {
+        self.parser.describe_into(rules)
+    }
+}
+
+/////////////////////////////////////////////////////////
+//                    OptionalField                    //
+/////////////////////////////////////////////////////////
+
+/// Field that can appear at most once.
+#[derive(Clone, Copy)]
+pub struct OptionalField<P> {
+    name: &'static str,
+    parser: P,
+}
+
+/// Creates a field that can appear at most once in a [`permutation`].
+///
+/// # Arguments
+/// * `name`: The name of the field, used when reporting errors.
+/// * `parser`: The parser for the whole field, including its key.
+///
+/// # Returns
+/// A field whose value is an `Option` with the output of `parser`.
+pub fn optional_field<'a, P: Parser<'a>>(name: &'static str, parser: P) -> OptionalField<P> {
+    OptionalField { name, parser }
+}
+
+impl<'a, P: Parser<'a>> FieldParser<'a> for OptionalField<P> {
+    type Parsed = P::Output;
+    type Value = Option<P::Output>;
+
+    fn name(&self) -> &'static str {
+        self.name
+    }
+
+    fn parse_field(&self, input: &'a str) -> Option<(Self::Parsed, &'a str)> {
+        self.parser.parse(input)
+    }
+
+    fn finish(&self, parsed: Option<Self::Parsed>) -> Result<Self::Value, FieldError> {
+        Ok(parsed)
+    }
+
+    fn describe_field(&self, rules: &mut Rules) -> Ebnf {
+        self.parser.describe_into(rules).postfix("?")
+    }
+}
+
+/// The fields of a record.
+///
+/// It is implemented for tuples of up to 12 fields.
+pub trait FieldParsers<'a> {
+    /// The values of the fields.
+    type Output;
+    /// The outputs of the fields parsed so far.
+    type Slots: Default;
+
+    /// Parses any of the fields and stores its output in `slots`.
+    ///
+    /// # Returns
+    /// The remaining input if a field was parsed, `None` if no field matches,
+    /// or an error if the field that matched was already parsed.
+    fn parse_any(
+        &self,
+        slots: &mut Self::Slots,
+        input: &'a str,
+    ) -> Result<Option<&'a str>, FieldError>;
+
+    /// Produces the values of the fields once the record has been parsed.
+    fn finish(&self, slots: Self::Slots) -> Result<Self::Output, FieldError>;
+
+    /// Describes each field, as in [`Parser::describe_into`].
+    fn describe_fields(&self, rules: &mut Rules) -> Vec<Ebnf>;
+}
+
+macro_rules! field_parsers {
+    ($(($($F:ident $i:tt),+);)+) => {$(
+        impl<'a, $($F: FieldParser<'a>),+> FieldParsers<'a> for ($($F,)+) {
+            type Output = ($($F::Value,)+);
+            type Slots = ($(Option<$F::Parsed>,)+);
+
+            fn parse_any(
+                &self,
+                slots: &mut Self::Slots,
+                input: &'a str,
+            ) -> Result<Option<&'a str>, FieldError> {
+                $(
+                    if let Some((parsed, remaining)) = self.$i.parse_field(input) {
+                        if slots.$i.is_some() {
+                            return Err(FieldError::Duplicate(self.$i.name()));
+                        }
+                        slots.$i = Some(parsed);
+                        return Ok(Some(remaining));
+                    }
+                )+
+                Ok(None)
+            }
+
+            fn finish(&self, slots: Self::Slots) -> Result<Self::Output, FieldError> {
+                Ok(($(self.$i.finish(slots.$i)?,)+))
+            }
+
+            fn describe_fields(&self, rules: &mut Rules) -> Vec<Ebnf> {
+                vec![$(self.$i.describe_field(rules)),+]
+            }
+        }
+    )+};
+}
+
+field_parsers! {
+    (F1 0, F2 1);
+    (F1 0, F2 1, F3 2);
+    (F1 0, F2 1, F3 2, F4 3);
+    (F1 0, F2 1, F3 2, F4 3, F5 4);
+    (F1 0, F2 1, F3 2, F4 3, F5 4, F6 5);
+    (F1 0, F2 1, F3 2, F4 3, F5 4, F6 5, F7 6);
+    (F1 0, F2 1, F3 2, F4 3, F5 4, F6 5, F7 6, F8 7);
+    (F1 0, F2 1, F3 2, F4 3, F5 4, F6 5, F7 6, F8 7, F9 8);
+    (F1 0, F2 1, F3 2, F4 3, F5 4, F6 5, F7 6, F8 7, F9 8, F10 9);
+    (F1 0, F2 1, F3 2, F4 3, F5 4, F6 5, F7 6, F8 7, F9 8, F10 9, F11 10);
+    (F1 0, F2 1, F3 2, F4 3, F5 4, F6 5, F7 6, F8 7, F9 8, F10 9, F11 10, F12 11);
+}
+
+///////////////////////////////////////////////////////
+//                    Permutation                    //
+///////////////////////////////////////////////////////
+
+/// Parser that parses a set of fields in any order.
+#[derive(Clone, Copy)]
+pub struct Permutation<Q, T> {
+    separator: Q,
+    fields: T,
+}
+
+/// Creates a parser that parses a set of fields in any order, separated by `separator`.
+///
+/// Parsing stops when no field matches after a separator. The parser fails if a required field
+/// is missing or a field appears twice; [`parse_fields`](ParserImpl::parse_fields) tells which.
+///
+/// # Arguments
+/// * `separator`: The parser between fields.
+/// * `fields`: A tuple of [`required_field`] and [`optional_field`] fields.
+///
+/// # Returns
+/// A new `Parser` that produces a tuple with the value of each field.
+///
+/// # Examples
+/// ```
+/// use aoc_utils::parser::*;
+///
+/// let passport = permutation(
+///     one_of(" \n"),
+///     (
+///         required_field("byr", prefix("byr:") >> number()),
+///         required_field("ecl", prefix("ecl:") >> take_while1(char::is_alphabetic)),
+///         optional_field("cid", prefix("cid:") >> number()),
+///     ),
+/// );
+///
+/// assert_eq!(passport.run_parser("ecl:gry byr:1937"), Some((1937, "gry", None)));
+/// assert_eq!(passport.run_parser("cid:147\nbyr:1929 ecl:amb"), Some((1929, "amb", Some(147))));
+/// assert_eq!(passport.run_parser("cid:147 byr:1929"), None);
+///
+/// assert_eq!(
+///     passport.parse_fields("ecl:gry byr:1937 ecl:amb"),
+///     Err(FieldError::Duplicate("ecl"))
+/// );
+/// assert_eq!(passport.parse_fields("byr:1937"), Err(FieldError::Missing("ecl")));
+/// ```
+pub fn permutation<'a, Q: Parser<'a>, T: FieldParsers<'a>>(
+    separator: Q,
+    fields: T,
+) -> ParserImpl<Permutation<Q, T>> {
+    ParserImpl(Permutation { separator, fields })
+}
+
+impl<'a, Q: Parser<'a>, T: FieldParsers<'a>> Permutation<Q, T> {
+    fn parse_fields(&self, mut input: &'a str) -> Result<(T::Output, &'a str), FieldError> {
+        let mut slots = T::Slots::default();
+
+        if let Some(remaining) = self.fields.parse_any(&mut slots, input)? {
+            input = remaining;
+
+            while let Some((_, start)) = self.separator.parse(input)
+                && let Some(remaining) = self.fields.parse_any(&mut slots, start)?
+            {
+                input = remaining;
+            }
+        }
+
+        Ok((self.fields.finish(slots)?, input))
+    }
+}
+
+impl<'a, Q: Parser<'a>, T: FieldParsers<'a>> ParserImpl<Permutation<Q, T>> {
+    /// Parses the fields like `parse`, but reports which field caused the failure.
+    ///
+    /// # Arguments
+    /// * `input`: The string slice to parse.
+    ///
+    /// # Returns
+    /// A `Result` with the values of the fields and the remaining string slice,
+    /// or the `FieldError` that made the parse fail.
+    pub fn parse_fields(&self, input: &'a str) -> Result<(T::Output, &'a str), FieldError> {
+        self.0.parse_fields(input)
+    }
+}
+
+impl<'a, Q: Parser<'a>, T: FieldParsers<'a>> Parser<'a> for Permutation<Q, T> {
+    type Output = T::Output;
+
+    fn parse(&self, input: &'a str) -> Option<(Self::Output, &'a str)> {
+        self.parse_fields(input).ok()
+    }
+
+    fn describe_into(&self, rules: &mut Rules) -> Ebnf {
+        let fields = self.fields.describe_fields(rules);
+        let separator = self.separator.describe_into(rules);
+
+        Ebnf::special(&format!(
+            "any order of {} separated by {separator}",
+            fields
+                .iter()
+                .map(Ebnf::to_string)
+                .collect::<Vec<_>>()
+                .join(", ")
+        ))
+    }
+}
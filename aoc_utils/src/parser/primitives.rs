@@ -1,7 +1,9 @@
 //! Primitive parsers for basic parsing tasks.
 //! These parsers can be combined to create more complex parsers by using the `Parser` trait.
 
-use super::{Parser, ParserImpl};
+use super::{Ebnf, Input, Lexeme, Parser, ParserImpl, Rules};
+
+use std::ops::RangeInclusive;
 
 ///////////////////////////////////////////////////
 //                    AnyChar                    //
@@ -21,17 +23,22 @@ pub struct AnyChar {}
 /// use aoc_utils::parser::*;
 ///
 /// assert_eq!(any_char().run_parser("blablabla"), Some('b'));
+/// assert_eq!(any_char().parse("ñx"), Some(('ñ', "x")));
 /// assert_eq!(any_char().run_parser(""), None);
 /// ```
 pub fn any_char() -> ParserImpl<AnyChar> {
     ParserImpl(AnyChar {})
 }
 
-impl Parser for AnyChar {
+impl<'a> Parser<'a> for AnyChar {
     type Output = char;
 
-    fn parse<'a>(&self, input: &'a str) -> Option<(Self::Output, &'a str)> {
-        input.chars().next().map(|c| (c, &input[1..]))
+    fn parse(&self, input: &'a str) -> Option<(Self::Output, &'a str)> {
+        input.chars().next().map(|c| (c, &input[c.len_utf8()..]))
+    }
+
+    fn describe_into(&self, _rules: &mut Rules) -> Ebnf {
+        Ebnf::special("any")
     }
 }
 
@@ -59,11 +66,15 @@ pub fn eof() -> ParserImpl<Eof> {
     ParserImpl(Eof {})
 }
 
-impl Parser for Eof {
+impl<'a, I: Input + ?Sized> Parser<'a, I> for Eof {
     type Output = ();
 
-    fn parse<'a>(&self, input: &'a str) -> Option<(Self::Output, &'a str)> {
-        input.is_empty().then_some(((), ""))
+    fn parse(&self, input: &'a I) -> Option<(Self::Output, &'a I)> {
+        input.is_empty().then_some(((), input))
+    }
+
+    fn describe_into(&self, _rules: &mut Rules) -> Ebnf {
+        Ebnf::special("eof")
     }
 }
 
@@ -98,15 +109,216 @@ pub fn satisfies<F: Fn(char) -> bool>(f: F) -> ParserImpl<Satisfies<F>> {
     ParserImpl(Satisfies { f })
 }
 
-impl<F: Fn(char) -> bool> Parser for Satisfies<F> {
+impl<'a, F: Fn(char) -> bool> Parser<'a> for Satisfies<F> {
     type Output = char;
 
-    fn parse<'a>(&self, input: &'a str) -> Option<(Self::Output, &'a str)> {
+    fn parse(&self, input: &'a str) -> Option<(Self::Output, &'a str)> {
         let mut chars = input.chars();
         chars
             .next()
             .and_then(|c| (self.f)(c).then_some((c, chars.as_str())))
     }
+
+    fn describe_into(&self, _rules: &mut Rules) -> Ebnf {
+        Ebnf::class(&self.f)
+    }
+}
+
+/////////////////////////////////////////////////////
+//                    CharClass                    //
+/////////////////////////////////////////////////////
+
+/// Parser that matches a single character belonging to a class.
+///
+/// Membership of ASCII characters is looked up in a table computed when the parser is created,
+/// the predicate is only evaluated for other characters.
+#[derive(Clone, Copy)]
+pub struct CharClass<F: Fn(char) -> bool> {
+    table: [bool; 128],
+    f: F,
+}
+
+fn char_class<F: Fn(char) -> bool>(f: F) -> ParserImpl<CharClass<F>> {
+    let mut table = [false; 128];
+    for (i, member) in table.iter_mut().enumerate() {
+        *member = f(i as u8 as char);
+    }
+
+    ParserImpl(CharClass { table, f })
+}
+
+impl<'a, F: Fn(char) -> bool> Parser<'a> for CharClass<F> {
+    type Output = char;
+
+    fn parse(&self, input: &'a str) -> Option<(Self::Output, &'a str)> {
+        let mut chars = input.chars();
+        chars.next().and_then(|c| {
+            let member = match self.table.get(c as usize) {
+                Some(&member) => member,
+                None => (self.f)(c),
+            };
+            member.then_some((c, chars.as_str()))
+        })
+    }
+
+    fn describe_into(&self, _rules: &mut Rules) -> Ebnf {
+        Ebnf::class(|c| self.table[c as usize])
+    }
+}
+
+/// Creates a parser that matches a single character contained in `set`.
+///
+/// # Arguments
+/// * `set`: The characters accepted by the parser.
+///
+/// # Returns
+/// A new `Parser` that produces the matched `char`.
+///
+/// # Examples
+/// ```
+/// use aoc_utils::parser::*;
+///
+/// let op = one_of("+-*/");
+///
+/// assert_eq!(op.run_parser("*2"), Some('*'));
+/// assert_eq!(op.run_parser("2*"), None);
+/// ```
+pub fn one_of(set: &str) -> ParserImpl<CharClass<impl Fn(char) -> bool + Copy>> {
+    char_class(move |c| set.contains(c))
+}
+
+/// Creates a parser that matches a single character not contained in `set`.
+///
+/// # Arguments
+/// * `set`: The characters rejected by the parser.
+///
+/// # Returns
+/// A new `Parser` that produces the matched `char`.
+///
+/// # Examples
+/// ```
+/// use aoc_utils::parser::*;
+///
+/// let not_bracket = none_of("[]");
+///
+/// assert_eq!(not_bracket.run_parser("a]"), Some('a'));
+/// assert_eq!(not_bracket.run_parser("]a"), None);
+/// assert_eq!(not_bracket.run_parser(""), None);
+/// ```
+pub fn none_of(set: &str) -> ParserImpl<CharClass<impl Fn(char) -> bool + Copy>> {
+    char_class(move |c| !set.contains(c))
+}
+
+/// Creates a parser that matches the character `c`.
+///
+/// # Arguments
+/// * `c`: The character to match.
+///
+/// # Returns
+/// A new `Parser` that produces the matched `char`.
+///
+/// # Examples
+/// ```
+/// use aoc_utils::parser::*;
+///
+/// assert_eq!(char(',').run_parser(",1"), Some(','));
+/// assert_eq!(char(',').run_parser("1,"), None);
+/// ```
+pub fn char(c: char) -> ParserImpl<CharClass<impl Fn(char) -> bool + Copy>> {
+    char_class(move |x| x == c)
+}
+
+/// Creates a parser that matches a single character inside an inclusive range.
+///
+/// # Arguments
+/// * `range`: The range of accepted characters.
+///
+/// # Returns
+/// A new `Parser` that produces the matched `char`.
+///
+/// # Examples
+/// ```
+/// use aoc_utils::parser::*;
+///
+/// let hex = char_range('0'..='9') | char_range('a'..='f');
+///
+/// assert_eq!(hex.run_parser("c0"), Some('c'));
+/// assert_eq!(hex.run_parser("g0"), None);
+/// ```
+pub fn char_range(
+    range: RangeInclusive<char>,
+) -> ParserImpl<CharClass<impl Fn(char) -> bool + Copy>> {
+    let (start, end) = range.into_inner();
+    char_class(move |c| start <= c && c <= end)
+}
+
+/// Creates a parser that matches a single ASCII digit.
+///
+/// # Returns
+/// A new `Parser` that produces the matched `char`.
+///
+/// # Examples
+/// ```
+/// use aoc_utils::parser::*;
+///
+/// assert_eq!(digit().run_parser("7a"), Some('7'));
+/// assert_eq!(digit().run_parser("a7"), None);
+/// ```
+pub fn digit() -> ParserImpl<CharClass<impl Fn(char) -> bool + Copy>> {
+    char_class(|c| c.is_ascii_digit())
+}
+
+/// Creates a parser that matches a single alphabetic character.
+///
+/// # Returns
+/// A new `Parser` that produces the matched `char`.
+///
+/// # Examples
+/// ```
+/// use aoc_utils::parser::*;
+///
+/// assert_eq!(alpha().run_parser("ab"), Some('a'));
+/// assert_eq!(alpha().run_parser("ñb"), Some('ñ'));
+/// assert_eq!(alpha().run_parser("1b"), None);
+/// ```
+pub fn alpha() -> ParserImpl<CharClass<impl Fn(char) -> bool + Copy>> {
+    char_class(char::is_alphabetic)
+}
+
+/// Creates a parser that matches a single alphanumeric character.
+///
+/// # Returns
+/// A new `Parser` that produces the matched `char`.
+///
+/// # Examples
+/// ```
+/// use aoc_utils::parser::*;
+///
+/// assert_eq!(alnum().run_parser("a1"), Some('a'));
+/// assert_eq!(alnum().run_parser("1a"), Some('1'));
+/// assert_eq!(alnum().run_parser("_a"), None);
+/// ```
+pub fn alnum() -> ParserImpl<CharClass<impl Fn(char) -> bool + Copy>> {
+    char_class(char::is_alphanumeric)
+}
+
+/// Creates a parser that matches a single space or tab, the characters [`spaces`] repeats.
+/// Line breaks are not matched, use [`ws`] to cross them.
+///
+/// # Returns
+/// A new `Parser` that produces the matched `char`.
+///
+/// # Examples
+/// ```
+/// use aoc_utils::parser::*;
+///
+/// assert_eq!(space().run_parser(" a"), Some(' '));
+/// assert_eq!(space().run_parser("\ta"), Some('\t'));
+/// assert_eq!(space().run_parser("\na"), None);
+/// assert_eq!(space().run_parser("a "), None);
+/// ```
+pub fn space() -> ParserImpl<CharClass<impl Fn(char) -> bool + Copy>> {
+    char_class(is_space)
 }
 
 //////////////////////////////////////////////////
@@ -140,12 +352,16 @@ pub fn prefix<'a>(p: &'a str) -> ParserImpl<Prefix<'a>> {
     ParserImpl(Prefix { p })
 }
 
-impl<'b> Parser for Prefix<'b> {
+impl<'a, 'b> Parser<'a> for Prefix<'b> {
     type Output = &'b str;
 
-    fn parse<'a>(&self, input: &'a str) -> Option<(Self::Output, &'a str)> {
+    fn parse(&self, input: &'a str) -> Option<(Self::Output, &'a str)> {
         input.strip_prefix(self.p).map(|s| (self.p, s))
     }
+
+    fn describe_into(&self, _rules: &mut Rules) -> Ebnf {
+        Ebnf::literal(self.p)
+    }
 }
 
 //////////////////////////////////////////////////
@@ -171,10 +387,10 @@ pub fn number() -> ParserImpl<Number> {
     ParserImpl(Number {})
 }
 
-impl Parser for Number {
+impl<'a> Parser<'a> for Number {
     type Output = usize;
 
-    fn parse<'a>(&self, input: &'a str) -> Option<(Self::Output, &'a str)> {
+    fn parse(&self, input: &'a str) -> Option<(Self::Output, &'a str)> {
         let fst_non_digit = input
             .char_indices()
             .find(|(_, c)| !c.is_ascii_digit())
@@ -186,6 +402,238 @@ impl Parser for Number {
             .ok()
             .map(|n| (n, &input[fst_non_digit..]))
     }
+
+    fn describe_into(&self, _rules: &mut Rules) -> Ebnf {
+        Ebnf::class(|c| c.is_ascii_digit()).postfix("+")
+    }
+}
+
+/////////////////////////////////////////////////////
+//                    TakeWhile                    //
+/////////////////////////////////////////////////////
+
+/// Parser that consumes the longest prefix of characters satisfying a given predicate.
+#[derive(Clone, Copy)]
+pub struct TakeWhile<F: Fn(char) -> bool> {
+    f: F,
+    non_empty: bool,
+}
+
+/// Creates a parser that consumes characters while they satisfy a given predicate.
+///
+/// Unlike `satisfies(f).repeat(..)`, the output borrows from the input instead of allocating.
+///
+/// # Arguments
+/// * `f`: A closure that takes a `char` and returns if the character satisfies a condition.
+///
+/// # Returns
+/// A new `Parser` that produces the consumed `&str`, which may be empty.
+///
+/// # Examples
+/// ```
+/// use aoc_utils::parser::*;
+///
+/// let word = take_while(char::is_alphabetic);
+///
+/// assert_eq!(word.parse("hello world"), Some(("hello", " world")));
+/// assert_eq!(word.parse("123"), Some(("", "123")));
+/// ```
+pub fn take_while<F: Fn(char) -> bool>(f: F) -> ParserImpl<TakeWhile<F>> {
+    ParserImpl(TakeWhile {
+        f,
+        non_empty: false,
+    })
+}
+
+/// Creates a parser that consumes at least one character while they satisfy a given predicate.
+///
+/// # Arguments
+/// * `f`: A closure that takes a `char` and returns if the character satisfies a condition.
+///
+/// # Returns
+/// A new `Parser` that produces the consumed `&str`.
+///
+/// # Examples
+/// ```
+/// use aoc_utils::parser::*;
+///
+/// let word = take_while1(char::is_alphabetic);
+///
+/// assert_eq!(word.run_parser("hello world"), Some("hello"));
+/// assert_eq!(word.run_parser("123"), None);
+/// ```
+pub fn take_while1<F: Fn(char) -> bool>(f: F) -> ParserImpl<TakeWhile<F>> {
+    ParserImpl(TakeWhile { f, non_empty: true })
+}
+
+impl<'a, F: Fn(char) -> bool> Parser<'a> for TakeWhile<F> {
+    type Output = &'a str;
+
+    fn parse(&self, input: &'a str) -> Option<(Self::Output, &'a str)> {
+        let end = input
+            .char_indices()
+            .find(|(_, c)| !(self.f)(*c))
+            .map_or(input.len(), |(i, _)| i);
+
+        (!self.non_empty || end > 0).then(|| input.split_at(end))
+    }
+
+    fn describe_into(&self, _rules: &mut Rules) -> Ebnf {
+        Ebnf::class(&self.f).postfix(if self.non_empty { "+" } else { "*" })
+    }
+}
+
+/////////////////////////////////////////////////////
+//                    TakeUntil                    //
+/////////////////////////////////////////////////////
+
+/// Parser that consumes input until a given pattern is found.
+#[derive(Clone, Copy)]
+pub struct TakeUntil<'b> {
+    p: &'b str,
+}
+
+/// Creates a parser that consumes input until a given pattern is found.
+///
+/// The pattern itself is not consumed. Fails if the pattern doesn't appear in the input.
+///
+/// # Arguments
+/// * `p`: The string slice to stop at.
+///
+/// # Returns
+/// A new `Parser` that produces the consumed `&str`.
+///
+/// # Examples
+/// ```
+/// use aoc_utils::parser::*;
+///
+/// let key = take_until(": ");
+///
+/// assert_eq!(key.parse("aaa: bbb"), Some(("aaa", ": bbb")));
+/// assert_eq!(key.parse("aaa bbb"), None);
+/// ```
+pub fn take_until<'b>(p: &'b str) -> ParserImpl<TakeUntil<'b>> {
+    ParserImpl(TakeUntil { p })
+}
+
+impl<'a, 'b> Parser<'a> for TakeUntil<'b> {
+    type Output = &'a str;
+
+    fn parse(&self, input: &'a str) -> Option<(Self::Output, &'a str)> {
+        input.find(self.p).map(|i| input.split_at(i))
+    }
+
+    fn describe_into(&self, _rules: &mut Rules) -> Ebnf {
+        Ebnf::special(&format!("until {:?}", self.p))
+    }
+}
+
+//////////////////////////////////////////////////////
+//                    Whitespace                    //
+//////////////////////////////////////////////////////
+
+/// Parser that consumes a run of whitespace characters.
+pub type Spaces = TakeWhile<fn(char) -> bool>;
+
+fn is_space(c: char) -> bool {
+    c == ' ' || c == '\t'
+}
+
+/// Creates a parser that consumes any number of spaces and tabs, the characters of [`space`].
+/// Line breaks are not consumed, use [`ws`] to cross them. Always succeeds.
+///
+/// # Returns
+/// A new `Parser` that produces the consumed `&str`.
+///
+/// # Examples
+/// ```
+/// use aoc_utils::parser::*;
+///
+/// assert_eq!(spaces().parse(" \t 1\n"), Some((" \t ", "1\n")));
+/// assert_eq!(spaces().parse("\n1"), Some(("", "\n1")));
+/// ```
+pub fn spaces() -> ParserImpl<Spaces> {
+    ParserImpl(TakeWhile {
+        f: is_space,
+        non_empty: false,
+    })
+}
+
+/// Creates a parser that consumes any amount of whitespace, as in `char::is_whitespace`, including
+/// line breaks. Always succeeds.
+///
+/// # Returns
+/// A new `Parser` that produces the consumed `&str`.
+///
+/// # Examples
+/// ```
+/// use aoc_utils::parser::*;
+///
+/// assert_eq!(ws().parse(" \n\t1"), Some((" \n\t", "1")));
+/// ```
+pub fn ws() -> ParserImpl<Spaces> {
+    ParserImpl(TakeWhile {
+        f: char::is_whitespace,
+        non_empty: false,
+    })
+}
+
+/// Parser that consumes a line break.
+#[derive(Clone, Copy)]
+pub struct Newline {}
+
+/// Creates a parser that consumes a line break, either `\n` or `\r\n`.
+///
+/// # Returns
+/// A new `Parser` that produces the consumed `&str`.
+///
+/// # Examples
+/// ```
+/// use aoc_utils::parser::*;
+///
+/// let lines = number().sep_by(newline(), ..);
+///
+/// assert_eq!(lines.run_parser("1\n2\r\n3"), Some(vec![1, 2, 3]));
+/// ```
+pub fn newline() -> ParserImpl<Newline> {
+    ParserImpl(Newline {})
+}
+
+impl<'a> Parser<'a> for Newline {
+    type Output = &'a str;
+
+    fn parse(&self, input: &'a str) -> Option<(Self::Output, &'a str)> {
+        ["\n", "\r\n"]
+            .into_iter()
+            .find(|nl| input.starts_with(nl))
+            .map(|nl| input.split_at(nl.len()))
+    }
+
+    fn describe_into(&self, _rules: &mut Rules) -> Ebnf {
+        Ebnf::alternative([Ebnf::literal("\n"), Ebnf::literal("\r\n")])
+    }
+}
+
+/// Creates a parser that consumes a prefix from the input and the spaces after it.
+///
+/// Equivalent to `prefix(p).lexeme()`.
+///
+/// # Arguments
+/// * `p`: The string slice to match as a prefix.
+///
+/// # Returns
+/// A new `Parser` that produces the prefix.
+///
+/// # Examples
+/// ```
+/// use aoc_utils::parser::*;
+///
+/// let list = number().lexeme().sep_by(symbol(","), ..).between(symbol("["), symbol("]"));
+///
+/// assert_eq!(list.run_parser("[ 1 ,2,  3 ]"), Some(vec![1, 2, 3]));
+/// ```
+pub fn symbol<'a>(p: &'a str) -> ParserImpl<Lexeme<ParserImpl<Prefix<'a>>>> {
+    prefix(p).lexeme()
 }
 
 ////////////////////////////////////////////////////
@@ -194,6 +642,8 @@ impl Parser for Number {
 
 /// Implementing `Parser<T>` for closures allows to define recursive parsers
 ///
+/// Functions and closures can be wrapped with [`p`](super::p) to combine them using operators.
+///
 /// # Examples
 /// ```
 /// use aoc_utils::parser::*;
@@ -225,10 +675,10 @@ impl Parser for Number {
 ///     ]))
 /// );
 /// ```
-impl<T, F: Fn(&str) -> Option<(T, &str)>> Parser for F {
+impl<'a, I: Input + ?Sized + 'a, T, F: Fn(&'a I) -> Option<(T, &'a I)>> Parser<'a, I> for F {
     type Output = T;
 
-    fn parse<'a>(&self, input: &'a str) -> Option<(Self::Output, &'a str)> {
+    fn parse(&self, input: &'a I) -> Option<(Self::Output, &'a I)> {
         self(input)
     }
 }
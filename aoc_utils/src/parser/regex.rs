@@ -0,0 +1,225 @@
+//! Parsers built from regular expressions, for tokens that are easier to describe that way.
+
+use super::{Ebnf, FromAocStr, Parser, ParserImpl, Rules};
+
+use std::marker::PhantomData;
+
+/////////////////////////////////////////////////
+//                    Regex                    //
+/////////////////////////////////////////////////
+
+/// Parser that matches a regular expression at the start of the input.
+#[derive(Clone)]
+pub struct Regex {
+    pattern: String,
+    regex: ::regex::Regex,
+}
+
+/// Creates a parser that matches a regular expression at the start of the input.
+///
+/// The match is anchored at the current position, so the parser fails instead of skipping input
+/// to find a match. The syntax is the one of the [`regex`](https://docs.rs/regex) crate.
+///
+/// # Arguments
+/// * `pattern`: The regular expression.
+///
+/// # Returns
+/// A new `Parser` that produces a `Vec<&str>` with the whole match followed by each capture group.
+/// Groups that didn't take part in the match are empty.
+///
+/// # Panics
+/// If `pattern` is not a valid regular expression.
+///
+/// # Examples
+/// ```
+/// use aoc_utils::parser::*;
+///
+/// let color = regex(r"#([0-9a-f]{2})([0-9a-f]{2})([0-9a-f]{2})");
+///
+/// assert_eq!(
+///     color.parse("#ff8000;"),
+///     Some((vec!["#ff8000", "ff", "80", "00"], ";"))
+/// );
+/// assert_eq!(color.parse(" #ff8000"), None);
+///
+/// // Regular expressions mix with the rest of the combinators.
+/// let words = regex(r"[a-z]+").map(|m| m[0]).sep_by(symbol(","), ..);
+/// assert_eq!(words.run_parser("ab, cd,ef"), Some(vec!["ab", "cd", "ef"]));
+/// ```
+pub fn regex(pattern: &str) -> ParserImpl<Regex> {
+    let regex = ::regex::Regex::new(&format!("^(?:{pattern})"))
+        .unwrap_or_else(|e| panic!("Invalid regular expression {pattern:?}: {e}"));
+
+    ParserImpl(Regex {
+        pattern: pattern.to_string(),
+        regex,
+    })
+}
+
+impl ParserImpl<Regex> {
+    /// Converts the capture groups of the match into a tuple, parsing each one with the type of
+    /// its element.
+    ///
+    /// Elements of type `&str` take the group as is, and the rest are parsed with
+    /// [`FromAocStr`], which must consume the whole group.
+    ///
+    /// # Returns
+    /// A new `Parser` that produces values of type `T`.
+    ///
+    /// # Panics
+    /// If the number of capture groups is not the size of the tuple.
+    ///
+    /// # Examples
+    /// ```
+    /// use aoc_utils::parser::*;
+    ///
+    /// let mul = regex(r"mul\((\d{1,3}),(\d{1,3})\)").captures::<(u32, u32)>();
+    ///
+    /// assert_eq!(mul.run_parser("mul(2,4)"), Some((2, 4)));
+    /// assert_eq!(mul.run_parser("mul(2,4"), None);
+    ///
+    /// let valve = regex(r"Valve (\w+) has flow rate=(-?\d+)").captures::<(&str, i32)>();
+    ///
+    /// assert_eq!(valve.run_parser("Valve AA has flow rate=7"), Some(("AA", 7)));
+    /// ```
+    pub fn captures<'a, T: FromCaptures<'a>>(self) -> ParserImpl<Captures<T>> {
+        let groups = self.0.regex.captures_len() - 1;
+        assert_eq!(
+            groups,
+            T::LEN,
+            "The regular expression {:?} has {groups} capture groups but the output has {} elements",
+            self.0.pattern,
+            T::LEN
+        );
+
+        ParserImpl(Captures {
+            regex: self.0,
+            output: PhantomData,
+        })
+    }
+}
+
+impl<'a> Parser<'a> for Regex {
+    type Output = Vec<&'a str>;
+
+    fn parse(&self, input: &'a str) -> Option<(Self::Output, &'a str)> {
+        let captures = self.regex.captures(input)?;
+        let end = captures.get(0)?.end();
+
+        let groups = captures
+            .iter()
+            .map(|group| group.map_or("", |group| group.as_str()))
+            .collect();
+
+        Some((groups, &input[end..]))
+    }
+
+    fn describe_into(&self, _rules: &mut Rules) -> Ebnf {
+        Ebnf::special(&format!("regex {:?}", self.pattern))
+    }
+}
+
+////////////////////////////////////////////////////
+//                    Captures                    //
+////////////////////////////////////////////////////
+
+/// Parser that converts the capture groups of a regular expression into a tuple.
+pub struct Captures<T> {
+    regex: Regex,
+    output: PhantomData<fn() -> T>,
+}
+
+impl<T> Clone for Captures<T> {
+    fn clone(&self) -> Self {
+        Captures {
+            regex: self.regex.clone(),
+            output: PhantomData,
+        }
+    }
+}
+
+impl<'a, T: FromCaptures<'a>> Parser<'a> for Captures<T> {
+    type Output = T;
+
+    fn parse(&self, input: &'a str) -> Option<(Self::Output, &'a str)> {
+        let (groups, remaining) = self.regex.parse(input)?;
+        T::from_captures(&groups[1..]).map(|output| (output, remaining))
+    }
+
+    fn describe_into(&self, rules: &mut Rules) -> Ebnf {
+        self.regex.describe_into(rules)
+    }
+}
+
+/// Values that can be converted from a capture group.
+pub trait FromCapture<'a>: Sized {
+    /// Converts a capture group.
+    ///
+    /// # Arguments
+    /// * `group`: The text of the group.
+    ///
+    /// # Returns
+    /// An `Option` with the value, or `None` if the group is not valid.
+    fn from_capture(group: &'a str) -> Option<Self>;
+}
+
+impl<'a> FromCapture<'a> for &'a str {
+    fn from_capture(group: &'a str) -> Option<Self> {
+        Some(group)
+    }
+}
+
+impl<'a, T: FromAocStr> FromCapture<'a> for T {
+    fn from_capture(group: &'a str) -> Option<Self> {
+        match T::parse_aoc(group) {
+            Some((value, "")) => Some(value),
+            _ => None,
+        }
+    }
+}
+
+/// Tuples that can be converted from the capture groups of a regular expression.
+pub trait FromCaptures<'a>: Sized {
+    /// The number of capture groups.
+    const LEN: usize;
+
+    /// Converts the capture groups, in order.
+    ///
+    /// # Arguments
+    /// * `groups`: The text of each group.
+    ///
+    /// # Returns
+    /// An `Option` with the tuple, or `None` if any group is not valid.
+    fn from_captures(groups: &[&'a str]) -> Option<Self>;
+}
+
+macro_rules! from_captures {
+    ($($len:literal ($($T:ident),+);)+) => {$(
+        impl<'a, $($T: FromCapture<'a>),+> FromCaptures<'a> for ($($T,)+) {
+            const LEN: usize = $len;
+
+            #[allow(non_snake_case)]
+            fn from_captures(groups: &[&'a str]) -> Option<Self> {
+                let [$($T),+] = groups else {
+                    return None;
+                };
+                Some(($($T::from_capture(*$T)?,)+))
+            }
+        }
+    )+};
+}
+
+from_captures! {
+    1 (A);
+    2 (A, B);
+    3 (A, B, C);
+    4 (A, B, C, D);
+    5 (A, B, C, D, E);
+    6 (A, B, C, D, E, F);
+    7 (A, B, C, D, E, F, G);
+    8 (A, B, C, D, E, F, G, H);
+    9 (A, B, C, D, E, F, G, H, I);
+    10 (A, B, C, D, E, F, G, H, I, J);
+    11 (A, B, C, D, E, F, G, H, I, J, K);
+    12 (A, B, C, D, E, F, G, H, I, J, K, L);
+}
@@ -0,0 +1,170 @@
+//! Parsing of inputs made of blocks separated by blank lines.
+
+use super::{Ebnf, Parser, ParserImpl, Rules};
+
+/// Splits the input into the blocks of lines separated by blank lines.
+///
+/// Lines that only contain whitespace are considered blank and consecutive blank lines are treated as one.
+/// The line break at the end of each block is not included.
+///
+/// # Arguments
+/// * `input`: The string slice to split.
+///
+/// # Returns
+/// A `Vec<&str>` with the non-empty blocks.
+///
+/// # Examples
+/// ```
+/// use aoc_utils::parser::*;
+///
+/// assert_eq!(split_sections("1\n2\n\n3\n\n\n4\n"), vec!["1\n2", "3", "4"]);
+/// assert_eq!(split_sections("\n"), Vec::<&str>::new());
+/// ```
+pub fn split_sections(input: &str) -> Vec<&str> {
+    let mut sections = vec![];
+    let mut start = None;
+    let mut end = 0;
+    let mut offset = 0;
+
+    for line in input.split_inclusive('\n') {
+        let content = line.trim_end_matches(['\n', '\r']);
+
+        if content.trim().is_empty() {
+            if let Some(s) = start.take() {
+                sections.push(&input[s..end]);
+            }
+        } else {
+            start.get_or_insert(offset);
+            end = offset + content.len();
+        }
+
+        offset += line.len();
+    }
+
+    if let Some(s) = start {
+        sections.push(&input[s..end]);
+    }
+
+    sections
+}
+
+/// A group of parsers, one for each block of an input separated by blank lines.
+///
+/// It is implemented for tuples of up to 12 parsers.
+pub trait SectionParsers<'a> {
+    type Output;
+
+    /// The number of blocks expected.
+    const COUNT: usize;
+
+    /// Parses each block with its parser. Every parser must consume the whole block.
+    ///
+    /// # Arguments
+    /// * `sections`: The blocks to parse, exactly `COUNT` of them.
+    ///
+    /// # Returns
+    /// A `Result` with the outputs of the parsers or the index of the first block that failed.
+    fn parse_sections(&self, sections: &[&'a str]) -> Result<Self::Output, usize>;
+
+    /// Describes the parser of each block, as in [`Parser::describe_into`].
+    fn describe_sections(&self, rules: &mut Rules) -> Vec<Ebnf>;
+}
+
+macro_rules! section_parsers {
+    ($(($($P:ident $i:tt),+);)+) => {$(
+        impl<'a, $($P: Parser<'a>),+> SectionParsers<'a> for ($($P,)+) {
+            type Output = ($($P::Output,)+);
+
+            const COUNT: usize = [$($i),+].len();
+
+            fn parse_sections(&self, sections: &[&'a str]) -> Result<Self::Output, usize> {
+                Ok(($(
+                    match self.$i.parse(sections[$i]) {
+                        Some((output, "")) => output,
+                        _ => return Err($i),
+                    },
+                )+))
+            }
+
+            fn describe_sections(&self, rules: &mut Rules) -> Vec<Ebnf> {
+                vec![$(self.$i.describe_into(rules)),+]
+            }
+        }
+    )+};
+}
+
+section_parsers! {
+    (P1 0, P2 1);
+    (P1 0, P2 1, P3 2);
+    (P1 0, P2 1, P3 2, P4 3);
+    (P1 0, P2 1, P3 2, P4 3, P5 4);
+    (P1 0, P2 1, P3 2, P4 3, P5 4, P6 5);
+    (P1 0, P2 1, P3 2, P4 3, P5 4, P6 5, P7 6);
+    (P1 0, P2 1, P3 2, P4 3, P5 4, P6 5, P7 6, P8 7);
+    (P1 0, P2 1, P3 2, P4 3, P5 4, P6 5, P7 6, P8 7, P9 8);
+    (P1 0, P2 1, P3 2, P4 3, P5 4, P6 5, P7 6, P8 7, P9 8, P10 9);
+    (P1 0, P2 1, P3 2, P4 3, P5 4, P6 5, P7 6, P8 7, P9 8, P10 9, P11 10);
+    (P1 0, P2 1, P3 2, P4 3, P5 4, P6 5, P7 6, P8 7, P9 8, P10 9, P11 10, P12 11);
+}
+
+////////////////////////////////////////////////////
+//                    Sections                    //
+////////////////////////////////////////////////////
+
+/// Parser that splits the input on blank lines and parses each block with its own parser.
+#[derive(Clone, Copy)]
+pub struct Sections<T> {
+    parsers: T,
+}
+
+/// Creates a parser that splits the whole input on blank lines and parses each block with its own parser.
+///
+/// Fails if the number of blocks differs from the number of parsers or if any parser
+/// doesn't consume its whole block.
+///
+/// # Arguments
+/// * `parsers`: A tuple with a parser for each block.
+///
+/// # Returns
+/// A new `Parser` that produces a tuple with the output of each parser.
+///
+/// # Examples
+/// ```
+/// use aoc_utils::parser::*;
+///
+/// let input = "1-3\n5-8\n\n2\n7\n";
+/// let ranges = (number() + (prefix("-") >> number())).sep_by(newline(), ..);
+/// let ids = number().sep_by(newline(), ..);
+///
+/// assert_eq!(
+///     sections((ranges, ids)).run_parser(input),
+///     Some((vec![(1, 3), (5, 8)], vec![2, 7]))
+/// );
+/// assert_eq!(sections((ranges, ids)).run_parser("1-3\n5-8"), None);
+/// ```
+pub fn sections<'a, T: SectionParsers<'a>>(parsers: T) -> ParserImpl<Sections<T>> {
+    ParserImpl(Sections { parsers })
+}
+
+impl<'a, T: SectionParsers<'a>> Parser<'a> for Sections<T> {
+    type Output = T::Output;
+
+    fn parse(&self, input: &'a str) -> Option<(Self::Output, &'a str)> {
+        let sections = split_sections(input);
+
+        (sections.len() == T::COUNT)
+            .then(|| self.parsers.parse_sections(&sections).ok())
+            .flatten()
+            .map(|output| (output, &input[input.len()..]))
+    }
+
+    fn describe_into(&self, rules: &mut Rules) -> Ebnf {
+        let blocks = self.parsers.describe_sections(rules);
+        let separator = Ebnf::special("blank line");
+
+        Ebnf::sequence(blocks.into_iter().enumerate().flat_map(|(i, block)| {
+            let separator = (i > 0).then(|| separator.clone());
+            separator.into_iter().chain([block])
+        }))
+    }
+}
@@ -0,0 +1,70 @@
+//! Positions of parsed values in the input.
+
+use super::{Ebnf, Input, Parser, Rules, context::Context};
+
+use std::ops::Range;
+
+////////////////////////////////////////////////////
+//                    WithSpan                    //
+////////////////////////////////////////////////////
+
+/// Parser that pairs the output of another parser with the range of bytes it consumed.
+#[derive(Clone, Copy)]
+pub struct WithSpan<P> {
+    pub(super) parser: P,
+}
+
+impl<'a, I: Input + ?Sized, P: Parser<'a, I>> Parser<'a, I> for WithSpan<P> {
+    type Output = (P::Output, Range<usize>);
+
+    fn parse(&self, input: &'a I) -> Option<(Self::Output, &'a I)> {
+        let (output, remaining) = self.parser.parse(input)?;
+
+        let start = Context::current()
+            .and_then(|context| context.offset_of(input))
+            .unwrap_or(0);
+        let end = start + input.len() - remaining.len();
+
+        Some(((output, start..end), remaining))
+    }
+
+    fn describe_into(&self, rules: &mut Rules) -> Ebnf {
+        self.parser.describe_into(rules)
+    }
+}
+
+/// Converts a byte offset into the line and column it falls on, both starting at 0.
+///
+/// The column counts characters, so it is the index of the character in its line.
+///
+/// # Arguments
+/// * `input`: The whole input the offset refers to.
+/// * `offset`: A byte offset into `input`, at most `input.len()`.
+///
+/// # Returns
+/// A tuple `(line, column)`.
+///
+/// # Panics
+/// If `offset` is out of bounds or not on a character boundary.
+///
+/// # Examples
+/// ```
+/// use aoc_utils::parser::*;
+///
+/// let input = "467..114\n..35..633";
+/// let numbers = number()
+///     .iter_matches(input)
+///     .map(|(n, span)| (n, line_col(input, span.start)))
+///     .collect::<Vec<_>>();
+///
+/// assert_eq!(numbers, vec![(467, (0, 0)), (114, (0, 5)), (35, (1, 2)), (633, (1, 6))]);
+/// ```
+pub fn line_col(input: &str, offset: usize) -> (usize, usize) {
+    let before = &input[..offset];
+    let line_start = before.rfind('\n').map_or(0, |i| i + 1);
+
+    (
+        before.matches('\n').count(),
+        before[line_start..].chars().count(),
+    )
+}
@@ -0,0 +1,194 @@
+//! User state shared by every parser taking part in a single run over an input.
+//!
+//! A [`StateSlot`] names a state of a given type. Parsers built with [`state`] and
+//! [`Parser::map_with_state`] take the slot they use, and [`Parser::parse_with_state`] lends a
+//! value to the slot while the parser runs, so things like symbol tables can be built during the
+//! parse. The state is a plain `&mut S`, so it can borrow from the input.
+//! Changes to the state are not undone when a parser fails and an alternative is tried.
+
+use super::{Ebnf, Input, Parser, ParserImpl, Rules};
+
+use std::{cell::Cell, ptr::NonNull, rc::Rc};
+
+/////////////////////////////////////////////////////
+//                    StateSlot                    //
+/////////////////////////////////////////////////////
+
+/// Slot through which parsers access a state of type `S` lent by
+/// [`parse_with_state`](Parser::parse_with_state).
+///
+/// Cloning the slot gives another handle to the same slot.
+pub struct StateSlot<S> {
+    current: Rc<Cell<Option<NonNull<S>>>>,
+}
+
+impl<S> StateSlot<S> {
+    /// Creates an empty slot.
+    ///
+    /// # Returns
+    /// A new `StateSlot`, that holds a state only while a parser runs with it.
+    pub fn new() -> Self {
+        StateSlot {
+            current: Rc::new(Cell::new(None)),
+        }
+    }
+
+    /// Runs `f` with `state` lent to the slot.
+    pub(super) fn enter<T>(&self, state: &mut S, f: impl FnOnce() -> T) -> T {
+        let _restore = Restore {
+            slot: &self.current,
+            previous: self.current.replace(Some(NonNull::from(state))),
+        };
+        f()
+    }
+
+    /// Calls `f` with the state lent to the slot.
+    ///
+    /// The state is taken out of the slot during the call, so `f` can run other parsers.
+    ///
+    /// # Panics
+    /// If there is no state, or it is already in use by an outer call.
+    fn with<T>(&self, f: impl FnOnce(&mut S) -> T) -> T {
+        let state = self.current.take().unwrap_or_else(|| {
+            panic!(
+                "Parser requires a state of type {} that is already in use or was not given, use parse_with_state",
+                std::any::type_name::<S>()
+            )
+        });
+        let _restore = Restore {
+            slot: &self.current,
+            previous: Some(state),
+        };
+        // SAFETY: the pointer comes from the `&mut S` given to `enter`, which outlives this call
+        // since `enter` takes it back from the slot before returning. Taking it out of the slot
+        // makes this the only reference in use until it is put back.
+        f(unsafe { &mut *state.as_ptr() })
+    }
+}
+
+impl<S> Default for StateSlot<S> {
+    fn default() -> Self {
+        StateSlot::new()
+    }
+}
+
+impl<S> Clone for StateSlot<S> {
+    fn clone(&self) -> Self {
+        StateSlot {
+            current: Rc::clone(&self.current),
+        }
+    }
+}
+
+/// Puts the previous content back in the slot when dropped, even if the parser panics.
+struct Restore<'s, S> {
+    slot: &'s Cell<Option<NonNull<S>>>,
+    previous: Option<NonNull<S>>,
+}
+
+impl<S> Drop for Restore<'_, S> {
+    fn drop(&mut self) {
+        self.slot.set(self.previous);
+    }
+}
+
+/////////////////////////////////////////////////
+//                    State                    //
+/////////////////////////////////////////////////
+
+/// Parser that reads or modifies the user state without consuming input.
+pub struct State<F, S> {
+    f: F,
+    slot: StateSlot<S>,
+}
+
+impl<F: Clone, S> Clone for State<F, S> {
+    fn clone(&self) -> Self {
+        State {
+            f: self.f.clone(),
+            slot: self.slot.clone(),
+        }
+    }
+}
+
+/// Creates a parser that calls `f` with the user state and produces its result without consuming input.
+///
+/// # Arguments
+/// * `slot`: The slot of the state.
+/// * `f`: A closure that takes a mutable reference to the state and returns a value `T`.
+///
+/// # Returns
+/// A new `Parser` that produces values of type `T`.
+///
+/// # Panics
+/// When parsing, if the parser isn't run with [`parse_with_state`](Parser::parse_with_state)
+/// and the same slot.
+///
+/// # Examples
+/// ```
+/// use aoc_utils::parser::*;
+///
+/// let lines = StateSlot::new();
+/// let line = (prefix("#") + state(&lines, |lines: &mut usize| *lines += 1)) << newline();
+/// let mut count = 0;
+///
+/// assert_eq!(
+///     line.repeat(..).parse_with_state(&lines, &mut count, "#\n#\n#\n").map(|v| v.len()),
+///     Some(3)
+/// );
+/// assert_eq!(count, 3);
+/// ```
+pub fn state<S, T, F: Fn(&mut S) -> T>(slot: &StateSlot<S>, f: F) -> ParserImpl<State<F, S>> {
+    ParserImpl(State {
+        f,
+        slot: slot.clone(),
+    })
+}
+
+impl<'a, I: Input + ?Sized, S, T, F: Fn(&mut S) -> T> Parser<'a, I> for State<F, S> {
+    type Output = T;
+
+    fn parse(&self, input: &'a I) -> Option<(Self::Output, &'a I)> {
+        Some((self.slot.with(&self.f), input))
+    }
+
+    fn describe_into(&self, _rules: &mut Rules) -> Ebnf {
+        Ebnf::literal("")
+    }
+}
+
+////////////////////////////////////////////////////////
+//                    MapWithState                    //
+////////////////////////////////////////////////////////
+
+/// Parser that transforms the output of another parser using a function with access to the user state.
+pub struct MapWithState<P, F, S> {
+    pub(super) parser: P,
+    pub(super) f: F,
+    pub(super) slot: StateSlot<S>,
+}
+
+impl<P: Clone, F: Clone, S> Clone for MapWithState<P, F, S> {
+    fn clone(&self) -> Self {
+        MapWithState {
+            parser: self.parser.clone(),
+            f: self.f.clone(),
+            slot: self.slot.clone(),
+        }
+    }
+}
+
+impl<'a, I: Input + ?Sized, S, U, P: Parser<'a, I>, F: Fn(&mut S, P::Output) -> U> Parser<'a, I>
+    for MapWithState<P, F, S>
+{
+    type Output = U;
+
+    fn parse(&self, input: &'a I) -> Option<(Self::Output, &'a I)> {
+        let (output, remaining) = self.parser.parse(input)?;
+        Some((self.slot.with(|state| (self.f)(state, output)), remaining))
+    }
+
+    fn describe_into(&self, rules: &mut Rules) -> Ebnf {
+        self.parser.describe_into(rules)
+    }
+}
@@ -0,0 +1,192 @@
+//! Tracing of parser runs, to find out why a grammar doesn't parse what it should.
+//!
+//! [`Parser::run_traced`] records a tree with every attempt of the parsers wrapped in
+//! [`Parser::trace`]. When the global debug switch is on ([`set_debug`]), [`Parser::run_parser`]
+//! records the tree too and prints it to stderr. Parsers not wrapped in [`Parser::trace`] are not
+//! recorded, so running them costs nothing extra.
+
+use super::{Ebnf, Input, Parser, Rules, context::Context, input::consumed};
+
+use std::{
+    cell::RefCell,
+    fmt,
+    sync::atomic::{AtomicBool, Ordering},
+};
+
+static DEBUG: AtomicBool = AtomicBool::new(false);
+
+thread_local! {
+    static STACK: RefCell<Option<Vec<Trace>>> = const { RefCell::new(None) };
+}
+
+/// Turns the global debug switch on or off.
+///
+/// # Arguments
+/// * `enabled`: Whether `run_parser` records the trace of each run and prints it to stderr.
+pub fn set_debug(enabled: bool) {
+    DEBUG.store(enabled, Ordering::Relaxed);
+}
+
+/// Returns whether the global debug switch is on.
+pub fn debug() -> bool {
+    DEBUG.load(Ordering::Relaxed)
+}
+
+/// An attempt of a parser, with the attempts of the parsers it ran.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct Trace {
+    /// The name of the parser.
+    pub label: String,
+    /// The position in the input where the attempt started, in bytes for `str`.
+    pub offset: usize,
+    /// The input consumed by the parser, rendered by [`Input::render`], or `None` if it failed.
+    pub consumed: Option<String>,
+    /// The attempts of the parsers it ran, in order.
+    pub children: Vec<Trace>,
+}
+
+impl Trace {
+    fn write(&self, f: &mut fmt::Formatter<'_>, depth: usize) -> fmt::Result {
+        write!(
+            f,
+            "{:indent$}{} @{} ",
+            "",
+            self.label,
+            self.offset,
+            indent = depth * 2
+        )?;
+        match &self.consumed {
+            Some(consumed) => writeln!(f, "ok {consumed:?}")?,
+            None => writeln!(f, "failed")?,
+        }
+
+        self.children
+            .iter()
+            .try_for_each(|child| child.write(f, depth + 1))
+    }
+}
+
+impl fmt::Display for Trace {
+    /// Writes the tree as an indented log, one attempt per line.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.write(f, 0)
+    }
+}
+
+/// Removes the current stack of attempts and restores the previous one when dropped, even if the parser panics.
+struct Restore(Option<Vec<Trace>>);
+
+impl Drop for Restore {
+    fn drop(&mut self) {
+        STACK.set(self.0.take());
+    }
+}
+
+/// Records the attempt of running `f` on `input` as a node labeled `label`, if a trace is being collected.
+pub(super) fn record<'a, I: Input + ?Sized, T>(
+    label: impl FnOnce() -> String,
+    input: &'a I,
+    f: impl FnOnce() -> Option<(T, &'a I)>,
+) -> Option<(T, &'a I)> {
+    let collecting = STACK.with_borrow_mut(|stack| match stack {
+        Some(stack) => {
+            stack.push(Trace {
+                label: label(),
+                offset: Context::current()
+                    .and_then(|context| context.offset_of(input))
+                    .unwrap_or(0),
+                consumed: None,
+                children: Vec::new(),
+            });
+            true
+        }
+        None => false,
+    });
+
+    if !collecting {
+        return f();
+    }
+
+    let result = f();
+
+    STACK.with_borrow_mut(|stack| {
+        if let Some(stack) = stack
+            && let Some(mut node) = stack.pop()
+        {
+            node.consumed = result
+                .as_ref()
+                .map(|(_, remaining)| input.split_at(consumed(input, remaining)).0.render());
+
+            if let Some(parent) = stack.last_mut() {
+                parent.children.push(node);
+            }
+        }
+    });
+
+    result
+}
+
+/// Runs `f` on `input` collecting the trace of its attempts under a root node labeled `label`.
+pub(super) fn collect<'a, I: Input + ?Sized, T>(
+    label: &str,
+    input: &'a I,
+    f: impl FnOnce() -> Option<(T, &'a I)>,
+) -> (Option<(T, &'a I)>, Trace) {
+    let root = Trace {
+        label: label.to_string(),
+        offset: 0,
+        consumed: None,
+        children: Vec::new(),
+    };
+
+    let restore = Restore(STACK.replace(Some(vec![root])));
+    let result = f();
+    let mut root = STACK
+        .take()
+        .and_then(|mut stack| stack.pop())
+        .expect("Trace stack is never empty while collecting");
+    drop(restore);
+
+    root.consumed = result
+        .as_ref()
+        .map(|(_, remaining)| input.split_at(consumed(input, remaining)).0.render());
+
+    (result, root)
+}
+
+/// Returns the name of a parser type without its path or generic arguments.
+pub(super) fn type_label<P: ?Sized>() -> String {
+    let name = std::any::type_name::<P>();
+    let name = name.split('<').next().unwrap_or(name);
+    match name.rsplit("::").next().unwrap_or(name) {
+        "{{closure}}" => "closure".to_string(),
+        name => name.to_string(),
+    }
+}
+
+//////////////////////////////////////////////////
+//                    Traced                    //
+//////////////////////////////////////////////////
+
+/// Parser that records its attempts in the trace of the run under a label.
+#[derive(Clone, Copy)]
+pub struct Traced<P> {
+    pub(super) parser: P,
+    pub(super) label: &'static str,
+}
+
+impl<'a, I: Input + ?Sized, P: Parser<'a, I>> Parser<'a, I> for Traced<P> {
+    type Output = P::Output;
+
+    fn parse(&self, input: &'a I) -> Option<(Self::Output, &'a I)> {
+        record(
+            || self.label.to_string(),
+            input,
+            || self.parser.parse(input),
+        )
+    }
+
+    fn describe_into(&self, rules: &mut Rules) -> Ebnf {
+        self.parser.describe_into(rules)
+    }
+}